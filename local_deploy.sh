@@ -48,3 +48,4 @@ deploy_project "stylus_erc20aton" "$PRIVATE_KEY"
 deploy_project "stylus_vault" "$PRIVATE_KEY"
 deploy_project "stylus_core_events" "$PRIVATE_KEY"
 deploy_project "stylus_stake_engine" "$PRIVATE_KEY"
+deploy_project "stylus_bet_slip" "$PRIVATE_KEY"
@@ -0,0 +1,442 @@
+// Allow `cargo stylus export-abi` to generate a main function.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+mod test;
+mod tools;
+use crate::tools::bytes8_to_string;
+
+use stylus_sdk::prelude::*;
+use stylus_sdk::{
+    abi::Bytes,
+    alloy_primitives::{Address, FixedBytes, Uint, U256},
+    alloy_sol_types::sol,
+    call::Call,
+    evm, msg,
+    types::AddressVM,
+};
+
+sol_interface! {
+    interface IERC721Receiver {
+        function onERC721Received(address operator, address from, uint256 token_id, bytes data) external returns (bytes4);
+    }
+}
+
+sol! {
+    // ERC-721 events.
+    event Transfer(address indexed from, address indexed to, uint256 indexed token_id);
+    event Approval(address indexed owner, address indexed approved, uint256 indexed token_id);
+    event ApprovalForAll(address indexed owner, address indexed operator, bool approved);
+
+    // Bet slip events.
+    event SlipMinted(uint256 indexed token_id, bytes8 event_id, uint8 team, uint256 amount);
+    event SlipIncreased(uint256 indexed token_id, uint256 amount);
+    event StakeEngineUpdated(address indexed stake_engine);
+
+    error AlreadyInitialized();                           // Thrown when the contract is initialized more than once.
+    error UnauthorizedAccount(address account);           // Thrown when the caller lacks the required role.
+    error ERC721NonexistentToken(uint256 token_id);       // Thrown when the token has never been minted or was burned.
+    error ERC721IncorrectOwner(address sender, uint256 token_id, address owner); // Thrown when `from` does not own the token.
+    error ERC721InsufficientApproval(address operator, uint256 token_id); // Thrown when the caller may not move the token.
+    error ERC721InvalidReceiver(address receiver);        // Thrown when the receiver is zero or rejects the token.
+    error ERC721InvalidOperator(address operator);        // Thrown when the operator is the zero address.
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum Error {
+    AlreadyInitialized(AlreadyInitialized),
+    UnauthorizedAccount(UnauthorizedAccount),
+    NonexistentToken(ERC721NonexistentToken),
+    IncorrectOwner(ERC721IncorrectOwner),
+    InsufficientApproval(ERC721InsufficientApproval),
+    InvalidReceiver(ERC721InvalidReceiver),
+    InvalidOperator(ERC721InvalidOperator),
+}
+
+/// ERC-165 interface id of ERC-165 itself.
+const ERC165_INTERFACE_ID: u32 = 0x01ffc9a7;
+/// ERC-165 interface id of ERC-721.
+const ERC721_INTERFACE_ID: u32 = 0x80ac58cd;
+/// Value `onERC721Received` must return to accept a token.
+const ERC721_RECEIVED: [u8; 4] = [0x15, 0x0b, 0x7a, 0x02];
+
+sol_storage! {
+    /// Transferable bet slips, one per stake position of the StakeEngine.
+    ///
+    /// # Fields
+    /// - `_owner`: Owner allowed to set the StakeEngine.
+    /// - `stake_engine`: The only account allowed to mint, increase and burn slips.
+    /// - `owners` / `balances`: ERC-721 ownership records.
+    /// - `token_approvals` / `operator_approvals`: ERC-721 approvals.
+    /// - `slips`: Position carried by each slip.
+    /// - `next_token_id`: Id of the next slip (ids start at 1, 0 means no slip).
+    #[entrypoint]
+    pub struct BetSlip {
+        address _owner;
+        address stake_engine;
+
+        mapping(uint256 => address) owners;
+        mapping(address => uint256) balances;
+        mapping(uint256 => address) token_approvals;
+        mapping(address => mapping(address => bool)) operator_approvals;
+
+        mapping(uint256 => Slip) slips;
+        uint256 next_token_id;
+    }
+
+    /// Stake position carried by a slip
+    pub struct Slip {
+        bytes8 event_id; // The event the stake was placed on.
+        uint8 team; // The outcome backed by the stake.
+        uint256 amount; // The amount staked.
+    }
+}
+
+#[public]
+impl BetSlip {
+    /// Initializes the contract with the StakeEngine allowed to manage slips.
+    ///
+    /// # Arguments
+    /// * `_stake_engine` - Address of the StakeEngine contract.
+    ///
+    /// # Returns
+    /// * `Ok(true)` on success, `Err(Error::AlreadyInitialized)` if called twice.
+    pub fn initialize(&mut self, _stake_engine: Address) -> Result<bool, Error> {
+        if self._owner.get() != Address::ZERO {
+            return Err(Error::AlreadyInitialized(AlreadyInitialized {}));
+        }
+
+        self._owner.set(msg::sender());
+        self.stake_engine.set(_stake_engine);
+        self.next_token_id.set(U256::from(1));
+
+        Ok(true)
+    }
+
+    /// Returns the owner of the contract.
+    pub fn owner(&self) -> Address {
+        self._owner.get()
+    }
+
+    /// Replaces the StakeEngine allowed to manage slips. Only callable by the owner.
+    pub fn set_stake_engine(&mut self, _stake_engine: Address) -> Result<bool, Error> {
+        self.only_owner()?;
+
+        self.stake_engine.set(_stake_engine);
+        evm::log(StakeEngineUpdated {
+            stake_engine: _stake_engine,
+        });
+
+        Ok(true)
+    }
+
+    /// Returns the StakeEngine allowed to manage slips.
+    pub fn stake_engine(&self) -> Address {
+        self.stake_engine.get()
+    }
+
+    /// Immutable collection name
+    pub fn name(&self) -> String {
+        "Arenaton Bet Slip".into()
+    }
+
+    /// Immutable collection symbol
+    pub fn symbol(&self) -> String {
+        "SLIP".into()
+    }
+
+    /// Whether the contract implements the interface `interface_id` (ERC-165).
+    pub fn supports_interface(&self, interface_id: FixedBytes<4>) -> bool {
+        let id = u32::from_be_bytes(interface_id.0);
+        id == ERC165_INTERFACE_ID || id == ERC721_INTERFACE_ID
+    }
+
+    /// Number of slips held by `owner`.
+    pub fn balance_of(&self, owner: Address) -> U256 {
+        self.balances.get(owner)
+    }
+
+    /// Current holder of `token_id`.
+    ///
+    /// # Returns
+    /// * The holder, or `Err(Error::NonexistentToken)` if the slip does not exist.
+    pub fn owner_of(&self, token_id: U256) -> Result<Address, Error> {
+        self._require_owned(token_id)
+    }
+
+    /// Position carried by `token_id`.
+    ///
+    /// # Returns
+    /// * `(event_id, team, amount)`, or `Err(Error::NonexistentToken)` if the slip does not exist.
+    pub fn slip(&self, token_id: U256) -> Result<(String, u8, U256), Error> {
+        self._require_owned(token_id)?;
+
+        let slip = self.slips.get(token_id);
+        Ok((
+            bytes8_to_string(slip.event_id.get()),
+            slip.team.get().to::<u8>(),
+            slip.amount.get(),
+        ))
+    }
+
+    /// Total number of slips ever minted.
+    pub fn total_minted(&self) -> U256 {
+        self.next_token_id.get().saturating_sub(U256::from(1))
+    }
+
+    /// Approves `to` to move `token_id`. Callable by the holder or one of its operators.
+    pub fn approve(&mut self, to: Address, token_id: U256) -> Result<(), Error> {
+        let owner = self._require_owned(token_id)?;
+        let sender = msg::sender();
+
+        if sender != owner && !self.is_approved_for_all(owner, sender) {
+            return Err(Error::InsufficientApproval(ERC721InsufficientApproval {
+                operator: sender,
+                token_id,
+            }));
+        }
+
+        self.token_approvals.setter(token_id).set(to);
+        evm::log(Approval {
+            owner,
+            approved: to,
+            token_id,
+        });
+
+        Ok(())
+    }
+
+    /// Account approved to move `token_id`.
+    pub fn get_approved(&self, token_id: U256) -> Result<Address, Error> {
+        self._require_owned(token_id)?;
+        Ok(self.token_approvals.get(token_id))
+    }
+
+    /// Allows or revokes `operator` to move every slip of the caller.
+    pub fn set_approval_for_all(&mut self, operator: Address, approved: bool) -> Result<(), Error> {
+        if operator == Address::ZERO {
+            return Err(Error::InvalidOperator(ERC721InvalidOperator { operator }));
+        }
+
+        let owner = msg::sender();
+        self.operator_approvals
+            .setter(owner)
+            .setter(operator)
+            .set(approved);
+        evm::log(ApprovalForAll {
+            owner,
+            operator,
+            approved,
+        });
+
+        Ok(())
+    }
+
+    /// Whether `operator` may move every slip of `owner`.
+    pub fn is_approved_for_all(&self, owner: Address, operator: Address) -> bool {
+        self.operator_approvals.getter(owner).get(operator)
+    }
+
+    /// Moves `token_id` from `from` to `to`.
+    ///
+    /// The new holder receives the payout of the position when the event is paid.
+    pub fn transfer_from(&mut self, from: Address, to: Address, token_id: U256) -> Result<(), Error> {
+        self._transfer_from(from, to, token_id)
+    }
+
+    /// Moves `token_id` from `from` to `to`, checking that a contract receiver accepts it.
+    #[selector(name = "safeTransferFrom")]
+    pub fn safe_transfer_from(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+    ) -> Result<(), Error> {
+        self.safe_transfer_from_with_data(from, to, token_id, Bytes(Vec::new()))
+    }
+
+    /// Moves `token_id` from `from` to `to` with `data` forwarded to a contract receiver.
+    #[selector(name = "safeTransferFrom")]
+    pub fn safe_transfer_from_with_data(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+        data: Bytes,
+    ) -> Result<(), Error> {
+        self._transfer_from(from, to, token_id)?;
+        self._check_on_received(from, to, token_id, data)
+    }
+
+    /// Mints a slip for a new position. Only callable by the StakeEngine.
+    ///
+    /// # Arguments
+    /// * `to` - The player who placed the stake.
+    /// * `event_id` - The event the stake was placed on.
+    /// * `team` - The outcome backed by the stake.
+    /// * `amount` - The amount staked.
+    ///
+    /// # Returns
+    /// * The id of the new slip.
+    pub fn mint(
+        &mut self,
+        to: Address,
+        event_id: FixedBytes<8>,
+        team: u8,
+        amount: U256,
+    ) -> Result<U256, Error> {
+        self.only_stake_engine()?;
+
+        if to == Address::ZERO {
+            return Err(Error::InvalidReceiver(ERC721InvalidReceiver { receiver: to }));
+        }
+
+        // Ids start at 1 so that 0 can mean "no slip" on the StakeEngine side.
+        let token_id = self.next_token_id.get().max(U256::from(1));
+        self.next_token_id.set(token_id + U256::from(1));
+
+        let mut slip = self.slips.setter(token_id);
+        slip.event_id.set(event_id);
+        slip.team.set(Uint::<8, 1>::from(team));
+        slip.amount.set(amount);
+
+        self._update(Address::ZERO, to, token_id);
+        evm::log(SlipMinted {
+            token_id,
+            event_id,
+            team,
+            amount,
+        });
+
+        Ok(token_id)
+    }
+
+    /// Adds `amount` to the position of `token_id`. Only callable by the StakeEngine.
+    pub fn increase(&mut self, token_id: U256, amount: U256) -> Result<(), Error> {
+        self.only_stake_engine()?;
+        self._require_owned(token_id)?;
+
+        let mut slip = self.slips.setter(token_id);
+        let total = slip.amount.get() + amount;
+        slip.amount.set(total);
+
+        evm::log(SlipIncreased { token_id, amount });
+
+        Ok(())
+    }
+
+    /// Burns `token_id` once its position is paid. Only callable by the StakeEngine.
+    pub fn burn(&mut self, token_id: U256) -> Result<(), Error> {
+        self.only_stake_engine()?;
+
+        let owner = self._require_owned(token_id)?;
+        self._update(owner, Address::ZERO, token_id);
+
+        Ok(())
+    }
+}
+
+impl BetSlip {
+    /// Holder of `token_id`, or `Err(Error::NonexistentToken)`.
+    fn _require_owned(&self, token_id: U256) -> Result<Address, Error> {
+        let owner = self.owners.get(token_id);
+        if owner == Address::ZERO {
+            return Err(Error::NonexistentToken(ERC721NonexistentToken { token_id }));
+        }
+        Ok(owner)
+    }
+
+    fn _transfer_from(&mut self, from: Address, to: Address, token_id: U256) -> Result<(), Error> {
+        if to == Address::ZERO {
+            return Err(Error::InvalidReceiver(ERC721InvalidReceiver { receiver: to }));
+        }
+
+        let owner = self._require_owned(token_id)?;
+        if owner != from {
+            return Err(Error::IncorrectOwner(ERC721IncorrectOwner {
+                sender: from,
+                token_id,
+                owner,
+            }));
+        }
+
+        let sender = msg::sender();
+        if sender != owner
+            && !self.is_approved_for_all(owner, sender)
+            && self.token_approvals.get(token_id) != sender
+        {
+            return Err(Error::InsufficientApproval(ERC721InsufficientApproval {
+                operator: sender,
+                token_id,
+            }));
+        }
+
+        self._update(from, to, token_id);
+
+        Ok(())
+    }
+
+    /// Moves `token_id` between `from` and `to`, zero meaning mint or burn.
+    fn _update(&mut self, from: Address, to: Address, token_id: U256) {
+        // Clear the approval of the previous holder.
+        self.token_approvals.delete(token_id);
+
+        if from != Address::ZERO {
+            let mut balance = self.balances.setter(from);
+            let old_balance = balance.get();
+            balance.set(old_balance - U256::from(1));
+        }
+
+        if to != Address::ZERO {
+            let mut balance = self.balances.setter(to);
+            let old_balance = balance.get();
+            balance.set(old_balance + U256::from(1));
+        }
+
+        self.owners.setter(token_id).set(to);
+        evm::log(Transfer { from, to, token_id });
+    }
+
+    /// Requires contract receivers to acknowledge the slip with `onERC721Received`.
+    fn _check_on_received(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+        data: Bytes,
+    ) -> Result<(), Error> {
+        if !to.has_code() {
+            return Ok(());
+        }
+
+        let receiver = IERC721Receiver::new(to);
+        let received = receiver
+            .on_erc_721_received(Call::new_in(self), msg::sender(), from, token_id, data.0.into())
+            .map_err(|_| Error::InvalidReceiver(ERC721InvalidReceiver { receiver: to }))?;
+
+        if received.0 != ERC721_RECEIVED {
+            return Err(Error::InvalidReceiver(ERC721InvalidReceiver { receiver: to }));
+        }
+
+        Ok(())
+    }
+
+    fn only_stake_engine(&self) -> Result<(), Error> {
+        if self.stake_engine.get() != msg::sender() {
+            return Err(Error::UnauthorizedAccount(UnauthorizedAccount {
+                account: msg::sender(),
+            }));
+        }
+        Ok(())
+    }
+
+    fn only_owner(&self) -> Result<(), Error> {
+        if self._owner.get() != msg::sender() {
+            return Err(Error::UnauthorizedAccount(UnauthorizedAccount {
+                account: msg::sender(),
+            }));
+        }
+        Ok(())
+    }
+}
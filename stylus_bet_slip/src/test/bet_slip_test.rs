@@ -0,0 +1,100 @@
+// tests/bet_slip_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::{BetSlip, Error};
+    use stylus_sdk::{
+        alloy_primitives::{address, Address, FixedBytes, U256},
+        msg,
+    };
+
+    const PLAYER: Address = address!("7e32b54800705876d3b5cfbc7d9c226a211f7c1a");
+    const BUYER: Address = address!("3f1eae7d46d88f08fc2f8ed27fcb2ab183eb2d0e");
+
+    fn event_id() -> FixedBytes<8> {
+        FixedBytes::<8>::from(*b"WXHG1234")
+    }
+
+    #[motsu::test]
+    fn initialize(contract: BetSlip) {
+        assert!(contract.initialize(msg::sender()).unwrap_or_default());
+        assert_eq!(contract.owner(), msg::sender());
+        assert_eq!(contract.stake_engine(), msg::sender());
+        assert!(matches!(
+            contract.initialize(msg::sender()),
+            Err(Error::AlreadyInitialized(_))
+        ));
+
+        assert_eq!(contract.symbol(), "SLIP");
+        assert!(contract.supports_interface(FixedBytes::<4>::from([0x80, 0xac, 0x58, 0xcd])));
+        assert!(!contract.supports_interface(FixedBytes::<4>::from([0xff, 0xff, 0xff, 0xff])));
+    }
+
+    #[motsu::test]
+    fn mint_increase_burn(contract: BetSlip) {
+        // Only the StakeEngine manages slips.
+        assert!(matches!(
+            contract.mint(PLAYER, event_id(), 1, U256::from(100)),
+            Err(Error::UnauthorizedAccount(_))
+        ));
+        contract.initialize(msg::sender()).unwrap_or_default();
+
+        let token_id = contract
+            .mint(PLAYER, event_id(), 1, U256::from(100))
+            .unwrap_or_default();
+        assert_eq!(token_id, U256::from(1));
+        assert_eq!(contract.owner_of(token_id).unwrap_or_default(), PLAYER);
+        assert_eq!(contract.balance_of(PLAYER), U256::from(1));
+
+        contract.increase(token_id, U256::from(50)).unwrap_or_default();
+        let (event, team, amount) = contract.slip(token_id).unwrap_or_default();
+        assert_eq!(event, "WXHG1234");
+        assert_eq!(team, 1);
+        assert_eq!(amount, U256::from(150));
+
+        contract.burn(token_id).unwrap_or_default();
+        assert_eq!(contract.balance_of(PLAYER), U256::ZERO);
+        assert!(matches!(
+            contract.owner_of(token_id),
+            Err(Error::NonexistentToken(_))
+        ));
+        assert_eq!(contract.total_minted(), U256::from(1));
+    }
+
+    #[motsu::test]
+    fn transfer(contract: BetSlip) {
+        contract.initialize(msg::sender()).unwrap_or_default();
+        let owned = contract
+            .mint(msg::sender(), event_id(), 2, U256::from(100))
+            .unwrap_or_default();
+        let foreign = contract
+            .mint(PLAYER, event_id(), 2, U256::from(100))
+            .unwrap_or_default();
+
+        // The caller can move its own slip, not somebody else's.
+        assert!(matches!(
+            contract.transfer_from(msg::sender(), Address::ZERO, owned),
+            Err(Error::InvalidReceiver(_))
+        ));
+        assert!(matches!(
+            contract.transfer_from(PLAYER, BUYER, foreign),
+            Err(Error::InsufficientApproval(_))
+        ));
+        assert!(matches!(
+            contract.transfer_from(PLAYER, BUYER, owned),
+            Err(Error::IncorrectOwner(_))
+        ));
+
+        contract.approve(PLAYER, owned).unwrap_or_default();
+        assert_eq!(contract.get_approved(owned).unwrap_or_default(), PLAYER);
+
+        contract
+            .safe_transfer_from(msg::sender(), BUYER, owned)
+            .unwrap_or_default();
+        assert_eq!(contract.owner_of(owned).unwrap_or_default(), BUYER);
+        assert_eq!(contract.balance_of(BUYER), U256::from(1));
+        assert_eq!(contract.balance_of(msg::sender()), U256::ZERO);
+        // The approval does not follow the slip.
+        assert_eq!(contract.get_approved(owned).unwrap_or_default(), Address::ZERO);
+    }
+}
@@ -0,0 +1 @@
+pub mod bet_slip_test;
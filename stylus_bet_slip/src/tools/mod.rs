@@ -0,0 +1,10 @@
+use stylus_sdk::alloy_primitives::FixedBytes;
+
+pub fn bytes8_to_string(input: FixedBytes<8>) -> String {
+    // Handle potential trailing zeros by trimming them
+    if let Some(end) = input.0.iter().position(|&b| b == 0) {
+        String::from_utf8_lossy(&input.0[..end]).to_string()
+    } else {
+        String::from_utf8_lossy(&input.0).to_string()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,83 @@
+// src/event_id_set.rs
+//
+// Intentionally vendored: each contract crate is built and deployed on its own, so
+// stylus_core_events and stylus_stake_engine carry identical copies of this file.
+// Edit both together; `event_id_set_copies_match` in the StakeEngine tests fails if they drift.
+
+use stylus_sdk::alloy_primitives::{FixedBytes, U256};
+use stylus_sdk::prelude::*;
+
+sol_storage! {
+    /// Set of event IDs with constant-cost insertion, lookup and removal.
+    ///
+    /// `ids` keeps the members contiguous so they can be paginated, while `positions`
+    /// maps each member to its one-based index in `ids` (zero means "not a member").
+    pub struct EventIdSet {
+        /// The members, in insertion order until a removal swaps the last one into the gap.
+        bytes8[] ids;
+
+        /// One-based position of each member in `ids`.
+        mapping(bytes8 => uint256) positions;
+    }
+}
+
+impl EventIdSet {
+    /// Returns the number of members.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Returns `true` when the set has no members.
+    pub fn is_empty(&self) -> bool {
+        self.ids.len() == 0
+    }
+
+    /// Returns the member stored at `index`, if any.
+    pub fn get(&self, index: usize) -> Option<FixedBytes<8>> {
+        self.ids.get(index)
+    }
+
+    /// Returns `true` if `id` is a member.
+    pub fn contains(&self, id: FixedBytes<8>) -> bool {
+        self.positions.get(id) != U256::ZERO
+    }
+
+    /// Adds `id` to the set.
+    ///
+    /// # Returns
+    /// * `false` if `id` was already a member.
+    pub fn insert(&mut self, id: FixedBytes<8>) -> bool {
+        if self.contains(id) {
+            return false;
+        }
+        self.ids.push(id);
+        let position = U256::from(self.ids.len());
+        self.positions.setter(id).set(position);
+        true
+    }
+
+    /// Removes `id` from the set by moving the last member into its slot.
+    ///
+    /// # Returns
+    /// * `false` if `id` was not a member.
+    pub fn remove(&mut self, id: FixedBytes<8>) -> bool {
+        let position = self.positions.get(id);
+        if position == U256::ZERO {
+            return false;
+        }
+
+        let index = position.to::<usize>() - 1;
+        let last_index = self.ids.len() - 1;
+        if index < last_index {
+            if let Some(last_id) = self.ids.get(last_index) {
+                if let Some(mut slot) = self.ids.setter(index) {
+                    slot.set(last_id);
+                }
+                self.positions.setter(last_id).set(position);
+            }
+        }
+        self.ids.pop();
+        self.positions.delete(id);
+        true
+    }
+}
@@ -2,18 +2,19 @@
 extern crate alloc;
 
 // -- Internal Modules ---------------------------------------------------------
+mod event_id_set;
 mod test;
 mod tools;
 
+use crate::event_id_set::EventIdSet;
 use crate::tools::{bytes8_to_string, string_to_bytes8};
 
 use std::string::String;
 
 // -- Stylus Imports ----------------------------------------------------------
 use stylus_sdk::prelude::*;
-use stylus_sdk::storage::{StorageFixedBytes, StorageVec};
 use stylus_sdk::{
-    alloy_primitives::{Address, FixedBytes, Uint, U256},
+    alloy_primitives::{Address, Uint},
     alloy_sol_types::sol,
     block, evm, msg,
     stylus_proc::{public, sol_storage, SolidityError},
@@ -55,6 +56,13 @@ sol! {
         uint8 winner
     );
 
+    /// Emitted when an event is closed with the final scores submitted by the oracle.
+    event EventScores(
+        string event_id,
+        uint32 home_score,
+        uint32 away_score
+    );
+
     /// Emitted when ownership of this contract is transferred from `previous_owner` to `new_owner`.
     event OwnershipTransferred(
         address indexed previous_owner,
@@ -104,11 +112,11 @@ sol_storage! {
         /// Mapping from a player's address to a list of event IDs (bytes8) the player is involved in.
         mapping(address => bytes8[]) player_events;
 
-        /// An array tracking the IDs of currently "opened" events (status = 1).
-        bytes8[] opened_events;
+        /// An indexed set tracking the IDs of currently "opened" events (status = 1).
+        EventIdSet opened_events;
 
-        /// An array tracking the IDs of closed events (status = 2).
-        bytes8[] closed_events;
+        /// An indexed set tracking the IDs of closed events (status = 2).
+        EventIdSet closed_events;
 
         /// The address of the Oracle, authorized to perform specific actions.
         address oracle_address;
@@ -118,6 +126,20 @@ sol_storage! {
     }
 
     /// Structure representing a single event with necessary details for betting or any game logic.
+    ///
+    /// Every field is packed into a single slot, starting from the low-order bytes of the word:
+    ///
+    /// | Bytes | Field            |
+    /// |-------|------------------|
+    /// | 0-7   | `event_id_bytes` |
+    /// | 8-15  | `start_date`     |
+    /// | 16    | `status`         |
+    /// | 17    | `winner`         |
+    /// | 18    | `has_scores`     |
+    /// | 19-22 | `home_score`     |
+    /// | 23-26 | `away_score`     |
+    ///
+    /// Keep new fields small and appended so the struct stays within one slot.
     pub struct EventCore {
         /// Unique identifier for the event in bytes8 format.
         bytes8 event_id_bytes;
@@ -137,6 +159,15 @@ sol_storage! {
         /// 255 (or another sentinel) for a tie/canceled, etc.
         /// (Exact usage can be adjusted as needed.)
         uint8 winner;
+
+        /// Whether the oracle submitted final scores when closing the event.
+        bool has_scores;
+
+        /// Final score of Team A (home).
+        uint32 home_score;
+
+        /// Final score of Team B (away).
+        uint32 away_score;
     }
 }
 #[public] // Exposes these functions publicly (Stylus-specific).
@@ -212,7 +243,7 @@ impl CoreEvents {
         e.status.set(Uint::<8, 1>::from(1u8));  // 1 => opened.
 
         // Track this opened event.
-        self.opened_events.push(event_id_bytes);
+        self.opened_events.insert(event_id_bytes);
 
         // Emit the `AddEvent` log.
         evm::log(AddEvent {
@@ -245,29 +276,44 @@ impl CoreEvents {
             return Err(Error::WrongWinner(WrongWinner {}));
         }
 
-        let event_id_bytes = string_to_bytes8(&event_id);
-        let mut e = self.events.setter(event_id_bytes);
-
-        // Ensure event is "opened" (status=1).
-        if e.status.get() != Uint::<8, 1>::from(1u8) {
-            return Err(Error::WrongStatus(WrongStatus {}));
-        }
+        self._close_event(event_id, winner)
+    }
 
-        // The event must have started (block time >= event's start_date).
-        if e.start_date.get() < Uint::<64, 1>::from(block::timestamp()) {
-            return Err(Error::NotStartedYet(NotStartedYet {}));
-        }
+    /// Closes an event with its final numeric scores. The winner is derived from the scores
+    /// (1 if `home_score` is higher, 2 if `away_score` is higher, 0 for a tie).
+    /// This function requires `msg::sender()` to be the oracle.
+    ///
+    /// # Arguments
+    /// * `event_id` - The string identifier of the event.
+    /// * `home_score` - Final score of Team A (e.g. home goals).
+    /// * `away_score` - Final score of Team B (e.g. away goals).
+    ///
+    /// # Returns
+    /// * `Ok(true)` on success.
+    /// * `Err(Error::WrongStatus)` if the event is not currently opened (status=1).
+    /// * `Err(Error::NotStartedYet)` if the block timestamp is still before the event’s start date.
+    /// * `Err(...)` if the caller is not the oracle.
+    pub fn close_event_with_scores(
+        &mut self,
+        event_id: String,
+        home_score: u32,
+        away_score: u32,
+    ) -> Result<bool, Error> {
+        self.only_oracle()?;
 
-        // Mark the winner and close the event.
-        e.winner.set(Uint::<8, 1>::from(winner));
-        e.status.set(Uint::<8, 1>::from(2u8)); // 2 => closed.
-        self.closed_events.push(event_id_bytes);
+        let winner = CoreEvents::_winner_from_scores(home_score, away_score);
+        self._close_event(event_id.clone(), winner)?;
 
-        // Remove it from the opened events array.
-        CoreEvents::_remove_event(event_id_bytes, &mut self.opened_events)?;
+        let mut e = self.events.setter(string_to_bytes8(&event_id));
+        e.has_scores.set(true);
+        e.home_score.set(Uint::<32, 1>::from(home_score));
+        e.away_score.set(Uint::<32, 1>::from(away_score));
 
-        // Emit the `CloseEvent` log.
-        evm::log(CloseEvent { event_id, winner });
+        evm::log(EventScores {
+            event_id,
+            home_score,
+            away_score,
+        });
 
         Ok(true)
     }
@@ -393,6 +439,26 @@ impl CoreEvents {
         ))
     }
 
+    /// Retrieves the final scores of an event by its string ID.
+    ///
+    /// # Arguments
+    /// * `_event_id_string` - The string identifier of the event to retrieve.
+    ///
+    /// # Returns
+    /// * A tuple containing:
+    ///   1. Whether scores were submitted (`bool`),
+    ///   2. The home score (`u32`),
+    ///   3. The away score (`u32`).
+    pub fn get_event_scores(&self, _event_id_string: String) -> (bool, u32, u32) {
+        let event = self.events.get(string_to_bytes8(&_event_id_string));
+
+        (
+            event.has_scores.get(),
+            event.home_score.get().to::<u32>(),
+            event.away_score.get().to::<u32>(),
+        )
+    }
+
     // -------------------------------------------------------------------------
     // Ownership
     // -------------------------------------------------------------------------
@@ -442,54 +508,53 @@ impl CoreEvents {
 
 
 impl CoreEvents {
-    /// Removes a specific event, identified by `event_id_bytes`, from a `StorageVec`.
-    /// 
-    /// - Searches the provided `events` vector for a matching event ID.
-    /// - If found, swaps it with the last element and then pops the last element to maintain a compact array.
-    /// - If not found, returns an `Error::NotAuthorized`. (You may want to replace this with a more specific error type, such as "EventNotFound.")
+    /// Marks an opened event as closed with the given `winner`.
     ///
     /// # Arguments
-    /// * `event_id_bytes` - The bytes8 identifier of the event to remove.
-    /// * `events` - A mutable reference to the `StorageVec` containing the events.
+    /// * `event_id` - The string identifier of the event.
+    /// * `winner` - The winning outcome (0 => tie, 1 => Team A, 2 => Team B, 3 => canceled).
     ///
     /// # Returns
-    /// * `Ok(())` if the event was successfully removed.
-    /// * `Err(Error::NotAuthorized(...))` if the event was not found in `events`.
-    fn _remove_event(
-        event_id_bytes: FixedBytes<8>,
-        events: &mut StorageVec<StorageFixedBytes<8>>,
-    ) -> Result<(), Error> {
-        // Get the length of the events vector.
-        let length = events.len();
-
-        // Find the index of the event to remove.
-        let mut index_to_remove: Option<usize> = None;
-        for i in 0..length {
-            if let Some(event) = events.get(i) {
-                if event == event_id_bytes {
-                    index_to_remove = Some(i);
-                    break;
-                }
-            }
+    /// * `Ok(true)` on success.
+    /// * `Err(Error::WrongStatus)` if the event is not currently opened (status=1).
+    /// * `Err(Error::NotStartedYet)` if the block timestamp is still before the event’s start date.
+    fn _close_event(&mut self, event_id: String, winner: u8) -> Result<bool, Error> {
+        let event_id_bytes = string_to_bytes8(&event_id);
+        let mut e = self.events.setter(event_id_bytes);
+
+        // Ensure event is "opened" (status=1).
+        if e.status.get() != Uint::<8, 1>::from(1u8) {
+            return Err(Error::WrongStatus(WrongStatus {}));
         }
 
-        // If the event is not found, return an error.
-        if index_to_remove.is_none() {
-            return Err(Error::NotAuthorized(NotAuthorized {}));
+        // The event must have started (block time >= event's start_date).
+        if Uint::<64, 1>::from(block::timestamp()) < e.start_date.get() {
+            return Err(Error::NotStartedYet(NotStartedYet {}));
         }
 
-        // Swap the event to remove with the last element and pop it off.
-        let index = index_to_remove.unwrap();
-        if index < length - 1 {
-            // Replace the element at `index` with the last element.
-            if let Some(last_event) = events.get(length - 1) {
-                events.setter(index).unwrap().set(last_event);
-            }
+        // Mark the winner and close the event.
+        e.winner.set(Uint::<8, 1>::from(winner));
+        e.status.set(Uint::<8, 1>::from(2u8)); // 2 => closed.
+        self.closed_events.insert(event_id_bytes);
+
+        // Remove it from the opened events set.
+        if !self.opened_events.remove(event_id_bytes) {
+            return Err(Error::NotAuthorized(NotAuthorized {}));
         }
-        // Remove the last element.
-        events.pop();
 
-        Ok(())
+        // Emit the `CloseEvent` log.
+        evm::log(CloseEvent { event_id, winner });
+
+        Ok(true)
+    }
+
+    /// Derives the winner from final scores: 1 if Team A scored more, 2 if Team B did, 0 for a tie.
+    pub fn _winner_from_scores(home_score: u32, away_score: u32) -> u8 {
+        match home_score.cmp(&away_score) {
+            std::cmp::Ordering::Greater => 1,
+            std::cmp::Ordering::Less => 2,
+            std::cmp::Ordering::Equal => 0,
+        }
     }
 
     /// Checks if the caller (`msg::sender()`) is the oracle.
@@ -1,12 +1,56 @@
 #[cfg(test)]
 mod tests {
-    use crate::CoreEvents;
+    use crate::tools::string_to_bytes8;
+    use crate::{CoreEvents, Error, EventCore};
+    use std::time::{Duration, Instant};
     use stylus_sdk::{
-        alloy_primitives::{address, Address, U256},
+        alloy_primitives::{address, Address, FixedBytes, Uint, U256},
         block, msg,
         prelude::*,
+        storage::{StorageFixedBytes, StorageType, StorageVec},
     };
 
+    /// Linear-scan removal that `EventIdSet` replaced, kept to benchmark the two in one run.
+    fn scan_remove(ids: &mut StorageVec<StorageFixedBytes<8>>, id: FixedBytes<8>) -> bool {
+        let Some(index) = (0..ids.len()).find(|&i| ids.get(i) == Some(id)) else {
+            return false;
+        };
+        let last_index = ids.len() - 1;
+        if index < last_index {
+            if let (Some(last_id), Some(mut slot)) = (ids.get(last_index), ids.setter(index)) {
+                slot.set(last_id);
+            }
+        }
+        ids.pop();
+        true
+    }
+
+    /// Time of `rounds` removals of the newest of `size` opened events, as (scan, set).
+    fn time_removals(contract: &mut CoreEvents, size: u64, rounds: u32) -> (Duration, Duration) {
+        // Far away from the contract's own slots.
+        let mut scanned = unsafe { StorageVec::<StorageFixedBytes<8>>::new(U256::from(u64::MAX), 0) };
+        for i in 0..size {
+            let id = FixedBytes::<8>::from(i.to_be_bytes());
+            scanned.push(id);
+            contract.opened_events.insert(id);
+        }
+        let newest = FixedBytes::<8>::from((size - 1).to_be_bytes());
+
+        let started = Instant::now();
+        for _ in 0..rounds {
+            assert!(scan_remove(&mut scanned, newest));
+            scanned.push(newest);
+        }
+        let scan = started.elapsed();
+
+        let started = Instant::now();
+        for _ in 0..rounds {
+            assert!(contract.opened_events.remove(newest));
+            contract.opened_events.insert(newest);
+        }
+        (scan, started.elapsed())
+    }
+
     #[motsu::test]
     fn initialize(contract: CoreEvents) {
         let sender = msg::sender();
@@ -35,4 +79,125 @@ mod tests {
 
   
     }
+
+    #[motsu::test]
+    fn close_event_with_scores(contract: CoreEvents) {
+        contract.initialize().unwrap_or_default();
+        contract.set_oracle(msg::sender()).unwrap_or_default();
+
+        // The event starts at the current block time, so it can be closed right away.
+        let event_id = "WXHG1234".to_string();
+        contract
+            .add_event(event_id.clone(), block::timestamp())
+            .unwrap_or_default();
+        assert_eq!(contract.get_event_scores(event_id.clone()), (false, 0, 0));
+
+        assert!(contract
+            .close_event_with_scores(event_id.clone(), 1, 3)
+            .unwrap_or_default());
+        assert_eq!(contract.get_event_scores(event_id.clone()), (true, 1, 3));
+
+        let (_, status, winner) = contract.get_event(event_id.clone()).unwrap_or_default();
+        assert_eq!(status, 2);
+        assert_eq!(winner, 2);
+
+        // A closed event cannot be closed again.
+        assert!(contract.close_event_with_scores(event_id, 2, 2).is_err());
+
+        assert_eq!(CoreEvents::_winner_from_scores(2, 2), 0);
+        assert_eq!(CoreEvents::_winner_from_scores(3, 0), 1);
+    }
+
+    #[motsu::test]
+    fn close_only_after_start(contract: CoreEvents) {
+        contract.initialize().unwrap_or_default();
+        contract.set_oracle(msg::sender()).unwrap_or_default();
+
+        // An event that has not started yet cannot be closed.
+        let upcoming = "WXHG1234".to_string();
+        contract
+            .add_event(upcoming.clone(), block::timestamp() + 3_600)
+            .unwrap_or_default();
+        assert!(matches!(
+            contract.close_event(upcoming, 1),
+            Err(Error::NotStartedYet(_))
+        ));
+
+        // An event already under way can.
+        let started = "ABCR3570".to_string();
+        contract
+            .add_event(started.clone(), block::timestamp())
+            .unwrap_or_default();
+        contract
+            .events
+            .setter(string_to_bytes8(&started))
+            .start_date
+            .set(Uint::<64, 1>::from(block::timestamp() - 3_600));
+        assert!(contract.close_event(started, 2).unwrap_or_default());
+    }
+
+    #[motsu::test]
+    fn opened_and_closed_sets(contract: CoreEvents) {
+        contract.initialize().unwrap_or_default();
+        contract.set_oracle(msg::sender()).unwrap_or_default();
+
+        let ids = ["WXHG1234", "ABCR3570", "XGTY8844"];
+        for id in ids {
+            contract
+                .add_event(id.to_string(), block::timestamp())
+                .unwrap_or_default();
+        }
+        assert_eq!(contract.opened_events.len(), 3);
+
+        // Closing the first event moves the last one into its slot.
+        contract.close_event(ids[0].to_string(), 1).unwrap_or_default();
+        assert_eq!(contract.opened_events.len(), 2);
+        assert!(!contract.opened_events.contains(string_to_bytes8(ids[0])));
+        assert_eq!(contract.opened_events.get(0), Some(string_to_bytes8(ids[2])));
+        assert!(contract.closed_events.contains(string_to_bytes8(ids[0])));
+
+        contract.close_event(ids[2].to_string(), 2).unwrap_or_default();
+        contract.close_event(ids[1].to_string(), 2).unwrap_or_default();
+        assert!(contract.opened_events.is_empty());
+        assert_eq!(contract.closed_events.len(), 3);
+
+        let opened = contract.get_opened_event_list(10, 0).unwrap_or_default();
+        assert!(opened.is_empty());
+        let closed = contract.get_closed_event_list(10, 0).unwrap_or_default();
+        assert_eq!(closed[0].0, "ABCR3570");
+    }
+
+    #[motsu::test]
+    fn opened_set_outpaces_scan(contract: CoreEvents) {
+        // The scan grows with the number of opened events, the set does not.
+        let (scan, set) = time_removals(contract, 512, 20);
+        assert!(set < scan, "set {:?} should beat scan {:?}", set, scan);
+    }
+
+    #[motsu::test]
+    fn packed_event_core(contract: CoreEvents) {
+        assert_eq!(<EventCore as StorageType>::REQUIRED_SLOTS, 1);
+
+        // Writing one packed field must leave its neighbours intact.
+        let id = string_to_bytes8("WXHG1234");
+        {
+            let mut event = contract.events.setter(id);
+            event.event_id_bytes.set(id);
+            event.start_date.set(Uint::<64, 1>::MAX);
+            event.status.set(Uint::<8, 1>::from(2u8));
+            event.winner.set(Uint::<8, 1>::MAX);
+            event.has_scores.set(true);
+            event.home_score.set(Uint::<32, 1>::MAX);
+            event.away_score.set(Uint::<32, 1>::MAX);
+            event.winner.set(Uint::<8, 1>::from(1u8));
+        }
+
+        let (start_date, status, winner) = contract.get_event("WXHG1234".to_string()).unwrap_or_default();
+        assert_eq!((start_date, status, winner), (u64::MAX, 2, 1));
+        assert_eq!(
+            contract.get_event_scores("WXHG1234".to_string()),
+            (true, u32::MAX, u32::MAX)
+        );
+        assert_eq!(contract.events.get(id).event_id_bytes.get(), id);
+    }
 }
@@ -49,13 +49,13 @@ sol_interface! {
     ///
     /// # Methods
     /// - `playerCommission(address)`: Returns the accrued commission for a player.
-    /// - `clearCommission(address)`: Clears the commission for a player.
+    /// - `clearCommission(address,uint256)`: Clears the commission for a player, reporting what was paid.
     interface IVault {
         /// Retrieves the commission for a specific player.
         function playerCommission(address player) external view returns (uint256);
 
-        /// Clears the commission for a specific player.
-        function clearCommission(address player) external;
+        /// Clears the commission for a specific player, reporting the amount paid to them.
+        function clearCommission(address player, uint256 paid) external;
     }
 }
 
@@ -167,13 +167,7 @@ impl Erc20Aton {
         // Decreases allowance
         allowance.set(old_allowance - value);
 
-        self._pay_commissions(to, from).map_err(|_| {
-            Error::InsufficientBalance(ERC20InsufficientBalance {
-                sender: from,
-                balance: self.balances.get(from),
-                needed: value,
-            })
-        })?;
+        self._pay_commissions(to, from)?;
         // Calls the internal transfer function
         self._transfer(from, to, value)?;
 
@@ -232,13 +226,7 @@ impl Erc20Aton {
     pub fn transfer(&mut self, to: Address, amount: U256) -> Result<bool, Error> {
         let caller = msg::sender();
 
-        self._pay_commissions(to, caller).map_err(|_| {
-            Error::InsufficientBalance(ERC20InsufficientBalance {
-                sender: msg::sender(),
-                balance: self.balances.get(caller),
-                needed: amount,
-            })
-        })?;
+        self._pay_commissions(to, caller)?;
 
         // Perform the transfer
         self._transfer(caller, to, amount) // 100
@@ -601,11 +589,10 @@ impl Erc20Aton {
     }
 
     // Helper function to calculate commission
-    fn _player_commission(&mut self, vault: &IVault, account: Address) -> U256 {
+    fn _player_commission(&mut self, vault: &IVault, account: Address) -> Result<U256, Error> {
         vault
             .player_commission(Call::new_in(self), account)
             .map_err(|_| Error::Zero(Zero { account }))
-            .unwrap_or_default()
     }
 
     // Helper function to perform a transfer
@@ -625,37 +612,50 @@ impl Erc20Aton {
     }
 
     // Helper function to clear commission in the vault
-    fn _clear_commission(&mut self, vault: &IVault, account: Address) -> Result<(), Error> {
+    fn _clear_commission(&mut self, vault: &IVault, account: Address, paid: U256) -> Result<(), Error> {
         vault
-            .clear_commission(Call::new_in(self), account)
+            .clear_commission(Call::new_in(self), account, paid)
             .map_err(|_| Error::Zero(Zero { account }))
     }
 
+    // Pays commission out of the vault, returning the amount actually transferred
+    fn _pay_commission(&mut self, vault_address: Address, to: Address, amount: U256) -> U256 {
+        match self._perform_transfer(vault_address, to, amount) {
+            Ok(()) => amount,
+            Err(_) => U256::ZERO,
+        }
+    }
+
     pub fn _pay_commissions(&mut self, to: Address, from: Address) -> Result<(), Error> {
-        let vault_contract = IVault::new(self.vault_address.get());
+        // The vault settles both sides of the transfers it makes itself (a call back into it
+        // would be re-entrant), and there is nothing to settle before it is set.
+        let vault_address = self.vault_address.get();
+        if vault_address == Address::ZERO || msg::sender() == vault_address {
+            return Ok(());
+        }
+        let vault_contract = IVault::new(vault_address);
 
-        let from_commission = self._player_commission(&vault_contract, from);
-        let to_commission = self._player_commission(&vault_contract, to);
+        let from_commission = self._player_commission(&vault_contract, from)?;
+        let to_commission = self._player_commission(&vault_contract, to)?;
 
         let _to = to;
         let _from = from;
         let _owner = self.owner.get();
         let mut owner_commission = U256::from(0);
         if from == contract::address() || to == contract::address() {
-            owner_commission = self._player_commission(&vault_contract, to);
+            owner_commission = self._player_commission(&vault_contract, to)?;
         }
-        let vault_address = self.vault_address.get();
 
-        let _ = self._perform_transfer(vault_address, _to, to_commission);
-        let _ = self._perform_transfer(vault_address, _from, from_commission);
+        let to_paid = self._pay_commission(vault_address, _to, to_commission);
+        let from_paid = self._pay_commission(vault_address, _from, from_commission);
 
-        let _ = self._clear_commission(&vault_contract, to);
-        let _ = self._clear_commission(&vault_contract, from);
+        self._clear_commission(&vault_contract, to, to_paid)?;
+        self._clear_commission(&vault_contract, from, from_paid)?;
 
         if from == contract::address() || to == contract::address() {
-            let _ = self._perform_transfer(vault_address, _owner, owner_commission);
+            let owner_paid = self._pay_commission(vault_address, _owner, owner_commission);
 
-            let _ = self._clear_commission(&vault_contract, _owner);
+            self._clear_commission(&vault_contract, _owner, owner_paid)?;
         }
         return Ok(());
     }
@@ -3,20 +3,23 @@
 extern crate alloc;
 
 // Import modules and dependencies
+mod event_id_set;
 mod test;
 mod tools;
+use crate::event_id_set::EventIdSet;
 use crate::tools::{bytes8_to_string, string_to_bytes8};
 
 use std::string::String;
 use stylus_sdk::prelude::*;
 use stylus_sdk::{
+    abi::Bytes,
     alloy_primitives::FixedBytes,
-    alloy_primitives::Uint,
+    alloy_primitives::{address, Signed, Uint, B256, I256},
     alloy_primitives::{Address, U256},
     alloy_sol_types::sol,
     block,
-    call::Call,
-    contract, evm, msg,
+    call::{Call, RawCall},
+    contract, crypto, evm, msg,
     stylus_proc::{public, sol_storage, SolidityError},
 };
 
@@ -29,15 +32,136 @@ sol_interface! {
         function vault() external view returns (address);
     }
 
+    interface IERC20 {
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+        function transfer(address to, uint256 amount) external returns (bool);
+    }
+
+    interface IBetSlip {
+        function mint(address to, bytes8 event_id, uint8 team, uint256 amount) external returns (uint256);
+        function increase(uint256 token_id, uint256 amount) external;
+        function burn(uint256 token_id) external;
+        function ownerOf(uint256 token_id) external view returns (address);
+    }
+
     interface ICoreEvents {
         function getEvent(string calldata _event_id_string) external view returns (uint64, uint8, uint8);
+        function getEventScores(string calldata _event_id_string) external view returns (bool, uint32, uint32);
     }
 }
 
 // Define Solidity-compatible error and events
 sol! {
-    error ErrorCode();
+    error AlreadyInitialized();                             // Thrown when the contract is initialized more than once.
+    error UnauthorizedAccount(address account);             // Thrown when the caller lacks the required role.
+    error InvalidAddress(address account);                  // Thrown when a zero address is supplied.
+    error AtonCallFailed(address aton);                     // Thrown when the ATON contract cannot be queried.
+    error CollateralNotAllowed(address token);              // Thrown when a collateral token is not accepted.
+    error CollateralLocked(bytes8 event_id);                // Thrown when changing the collateral of an event holding stakes.
+    error EthNotAccepted(address collateral);               // Thrown when ETH is sent to an event with a non-ATON collateral.
+    error InvalidTeam(uint8 team);                          // Thrown when the team is neither 1 nor 2.
+    error TeamLocked(uint8 team);                           // Thrown when a player stakes on the other side of an event.
+    error WrongStatus(bytes8 event_id, uint8 status, uint8 expected); // Thrown when an event is not in the required status.
+    error AlreadyStarted(bytes8 event_id, uint64 start_date, uint64 timestamp); // Thrown when staking on an event that started.
+    error EventNotFound(bytes8 event_id);                   // Thrown when CoreEvents has no such event.
+    error PlayerEventNotFound(bytes8 event_id, address player); // Thrown when the event is not in the player's list.
+    error MintFailed(uint256 value);                        // Thrown when minting ATON from ETH fails.
+    error TransferFailed(address token, address from, address to, uint256 amount); // Thrown when a collateral transfer fails.
+    error SlipCallFailed(uint256 token_id);                 // Thrown when the bet slip contract rejects a call.
+    error UnknownSlip(uint256 token_id);                    // Thrown when no position was issued for the slip.
+    error InvalidLegCount(uint256 count);                   // Thrown when a parlay has too few or too many legs.
+    error DuplicateLeg(bytes8 event_id);                    // Thrown when a parlay uses the same event twice.
+    error LegNotPriced(bytes8 event_id, uint8 team);        // Thrown when a leg has no odds (empty pool).
+    error MixedCollateral(bytes8 event_id, address collateral); // Thrown when parlay legs use different collaterals.
+    error InsufficientParlayReserve(uint256 available, uint256 needed); // Thrown when the reserve cannot cover a ticket.
+    error ParlayNotOpen(uint256 ticket_id);                 // Thrown when settling a missing or settled ticket.
+    error InvalidMarketKind(uint8 kind);                    // Thrown when creating a market of an unknown kind.
+    error MarketWrongStatus(uint256 market_id, uint8 status); // Thrown when a market is not in the required status.
+    error InvalidOutcome(uint32 outcome);                   // Thrown when the outcome does not exist in the market.
+    error OutcomeLocked(uint32 outcome);                    // Thrown when a player stakes on another outcome of a market.
+    error NothingToClaim(uint256 market_id, address player); // Thrown when a market position is empty or already paid.
+    error InvalidOutcomeCount(uint256 count);               // Thrown when an outright has too few or too many outcomes.
+    error OutrightWrongStatus(uint256 outright_id, uint8 status); // Thrown when an outright is not in the required status.
+    error OutrightClosed(uint256 outright_id, uint64 close_time); // Thrown when staking on an outright after it closed.
+    error OutrightNotClosed(uint256 outright_id, uint64 close_time); // Thrown when settling an outright still open to stakes.
+    error SlipTransferred(uint256 token_id, address holder); // Thrown when adding to a position whose slip was transferred.
+    error SlippageExceeded(uint256 multiplier, uint256 min_multiplier); // Thrown when the odds moved below the player's minimum.
+    error DeadlineExpired(uint64 deadline, uint64 timestamp); // Thrown when a transaction or credit lands after its deadline.
+    error StakeBelowMinimum(uint256 amount, uint256 min_stake); // Thrown when a stake is under the minimum stake.
+    error PlayerStakeExceeded(uint256 stake, uint256 max_player_stake); // Thrown when a position exceeds the per-player cap.
+    error OutcomePoolExceeded(uint256 pool, uint256 max_outcome_pool); // Thrown when an outcome pool exceeds its cap.
+    error PoolImbalanceExceeded(uint256 imbalance, uint256 max_imbalance); // Thrown when a stake widens the pool gap past the cap.
+    error StakeOverflow(uint256 stake);                     // Thrown when a position no longer fits its packed field.
+    error SelfExcluded(address player, uint64 until);       // Thrown when a self-excluded player stakes.
+    error SpendLimitExceeded(uint8 period, uint256 spent, uint256 limit); // Thrown when a stake exceeds the player's spend limit.
+    error InvalidPeriod(uint8 period);                      // Thrown when the spend-limit period is unknown.
+    error InvalidSignature();                               // Thrown when a stake order signature is malformed or does not match.
+    error NonceUsed(address player, uint256 nonce);         // Thrown when a stake order nonce was used or canceled.
+    error ReferrerAlreadySet(address referrer);             // Thrown when the player is already bound to a referrer.
+    error InvalidReferrer(address referrer);                // Thrown when the referrer is the zero address or the player.
+    error InvalidShare(uint256 share);                      // Thrown when the referral share exceeds `PCT_DENOM`.
+    error CreditCapExceeded(uint256 outstanding, uint256 cap); // Thrown when issuing credit beyond the outstanding cap.
+    error InsufficientCredit(uint256 available, uint256 amount); // Thrown when staking more credit than the player holds.
+    error InsufficientPromoReserve(uint256 available, uint256 needed); // Thrown when the promo reserve cannot back the credit.
+    error CreditNotAccepted(address collateral);            // Thrown when staking credit on a non-ATON event.
+    error LiquidityNotAccepted(address collateral);         // Thrown when seeding a non-ATON event.
+    error NoLiquidity(bytes8 event_id, address provider);   // Thrown when a provider has no liquidity left in the event.
+    error NoBonus(bytes8 event_id, address sponsor);        // Thrown when the sponsor has no bonus to refund.
+    error BonusNotRefundable(bytes8 event_id);              // Thrown when the bonus goes to the event winners.
    
+    event CollateralUpdated(address indexed token, bool allowed);
+
+    event EventCollateralSet(bytes8 event_id, address indexed token);
+
+    event BetSlipUpdated(address indexed bet_slip);
+
+    event SpendLimitSet(address indexed player, address indexed token, uint8 period, uint256 limit, uint64 effective_at);
+    event SelfExclusionSet(address indexed player, uint64 until);
+
+    event StakeOrderFilled(address indexed player, address indexed relayer, uint256 nonce);
+    event NonceCanceled(address indexed player, uint256 nonce);
+
+    event ReferrerBound(address indexed player, address indexed referrer);
+    event ReferralShareUpdated(uint256 share);
+    event ReferralRewardAccrued(address indexed referrer, address indexed player, bytes8 event_id, uint256 amount);
+    event ReferralRewardClaimed(address indexed referrer, uint256 amount);
+    event ReferralRewardReleased(address indexed referrer, bytes8 event_id, uint256 amount);
+
+    event PromoterUpdated(address indexed account, bool allowed);
+    event PromoReserveUpdated(uint256 reserve, uint256 outstanding, uint256 cap);
+    event CreditIssued(address indexed player, uint256 amount, uint64 expires_at);
+    event CreditStaked(address indexed player, bytes8 event_id, uint256 amount);
+
+    event LiquidityProviderUpdated(address indexed account, bool allowed);
+    event LiquiditySeeded(bytes8 indexed event_id, address indexed provider, uint256 home_amount, uint256 away_amount);
+    event LiquidityWithdrawn(bytes8 indexed event_id, address indexed provider, uint256 amount);
+
+    event BonusSponsored(bytes8 indexed event_id, address indexed sponsor, uint256 amount);
+    event BonusPaid(bytes8 indexed event_id, address indexed player, uint256 amount);
+    event BonusRefunded(bytes8 indexed event_id, address indexed sponsor, uint256 amount);
+
+    event SeasonRolled(uint256 indexed season, uint64 started_at);
+
+    event ParlayPlaced(uint256 indexed ticket_id, address indexed player, uint256 amount, uint256 potential_payout);
+    event ParlaySettled(uint256 indexed ticket_id, address indexed player, uint256 payout);
+    event ParlayReserveUpdated(address indexed token, uint256 reserve);
+
+    event MarketCreated(uint256 indexed market_id, bytes8 event_id, uint8 kind, int64 line);
+    event MarketStake(uint256 indexed market_id, address indexed player, uint32 outcome, uint256 amount);
+    event MarketResolved(uint256 indexed market_id, uint8 status, uint32 winning_outcome);
+
+    event OutrightCreated(uint256 indexed outright_id, address collateral, uint64 close_time, uint8 outcome_count);
+    event OutrightStake(uint256 indexed outright_id, address indexed player, uint8 outcome, uint256 amount);
+    event OutrightSettled(uint256 indexed outright_id, uint8 status, uint8[] winners);
+
+    event StakeLimitsUpdated(
+        bytes8 event_id, // Zero for the default limits
+        uint256 min_stake,
+        uint256 max_player_stake,
+        uint256 max_outcome_pool,
+        uint256 max_imbalance
+    );
+
     event NewStake(
         bytes8  event_id,  // Changed from string to bytes8
         address  player,
@@ -49,170 +173,3115 @@ sol! {
 /// Represents the ways methods may fail.
 #[derive(SolidityError)]
 pub enum Error {
-    ErrorCode(ErrorCode),
+    AlreadyInitialized(AlreadyInitialized),
+    UnauthorizedAccount(UnauthorizedAccount),
+    InvalidAddress(InvalidAddress),
+    AtonCallFailed(AtonCallFailed),
+    CollateralNotAllowed(CollateralNotAllowed),
+    CollateralLocked(CollateralLocked),
+    EthNotAccepted(EthNotAccepted),
+    InvalidTeam(InvalidTeam),
+    TeamLocked(TeamLocked),
+    WrongStatus(WrongStatus),
+    AlreadyStarted(AlreadyStarted),
+    EventNotFound(EventNotFound),
+    PlayerEventNotFound(PlayerEventNotFound),
+    MintFailed(MintFailed),
+    TransferFailed(TransferFailed),
+    SlipCallFailed(SlipCallFailed),
+    UnknownSlip(UnknownSlip),
+    InvalidLegCount(InvalidLegCount),
+    DuplicateLeg(DuplicateLeg),
+    LegNotPriced(LegNotPriced),
+    MixedCollateral(MixedCollateral),
+    InsufficientParlayReserve(InsufficientParlayReserve),
+    ParlayNotOpen(ParlayNotOpen),
+    InvalidMarketKind(InvalidMarketKind),
+    MarketWrongStatus(MarketWrongStatus),
+    InvalidOutcome(InvalidOutcome),
+    OutcomeLocked(OutcomeLocked),
+    NothingToClaim(NothingToClaim),
+    InvalidOutcomeCount(InvalidOutcomeCount),
+    OutrightWrongStatus(OutrightWrongStatus),
+    OutrightClosed(OutrightClosed),
+    OutrightNotClosed(OutrightNotClosed),
+    SlipTransferred(SlipTransferred),
+    SlippageExceeded(SlippageExceeded),
+    DeadlineExpired(DeadlineExpired),
+    StakeBelowMinimum(StakeBelowMinimum),
+    PlayerStakeExceeded(PlayerStakeExceeded),
+    OutcomePoolExceeded(OutcomePoolExceeded),
+    PoolImbalanceExceeded(PoolImbalanceExceeded),
+    StakeOverflow(StakeOverflow),
+    SelfExcluded(SelfExcluded),
+    SpendLimitExceeded(SpendLimitExceeded),
+    InvalidPeriod(InvalidPeriod),
+    InvalidSignature(InvalidSignature),
+    NonceUsed(NonceUsed),
+    ReferrerAlreadySet(ReferrerAlreadySet),
+    InvalidReferrer(InvalidReferrer),
+    InvalidShare(InvalidShare),
+    CreditCapExceeded(CreditCapExceeded),
+    InsufficientCredit(InsufficientCredit),
+    InsufficientPromoReserve(InsufficientPromoReserve),
+    CreditNotAccepted(CreditNotAccepted),
+    LiquidityNotAccepted(LiquidityNotAccepted),
+    NoLiquidity(NoLiquidity),
+    NoBonus(NoBonus),
+    BonusNotRefundable(BonusNotRefundable),
 }
 
+/// Commission retained from a two-sided pool, in units of `PCT_DENOM` (2%).
+pub const COMMISSION_PREMIUM: u64 = 200_000;
+/// Denominator of percentage values.
+pub const PCT_DENOM: u64 = 10_000_000;
+/// Fixed-point scale of decimal odds (1.0 = 10^18).
+pub const ODDS_PRECISION: u64 = 1_000_000_000_000_000_000;
+/// Maximum number of legs of a parlay ticket.
+pub const MAX_PARLAY_LEGS: usize = 8;
+
+/// Market on the total score: outcome 1 wins over the line, outcome 2 under it.
+pub const MARKET_TOTALS: u8 = 1;
+/// Market on a handicap: outcome 1 wins if Team A covers the line, outcome 2 otherwise.
+pub const MARKET_SPREAD: u8 = 2;
+/// Market on the exact score: outcome `home << 16 | away`.
+pub const MARKET_EXACT_SCORE: u8 = 3;
+/// Scale of market lines (a line of 25 is 2.5 points).
+pub const LINE_SCALE: i64 = 10;
+
+/// Maximum number of outcomes of an outright market.
+pub const MAX_OUTRIGHT_OUTCOMES: usize = 64;
+
+/// Daily spend limit period.
+pub const PERIOD_DAILY: u8 = 0;
+/// Weekly spend limit period.
+pub const PERIOD_WEEKLY: u8 = 1;
+/// Monthly (30 days) spend limit period.
+pub const PERIOD_MONTHLY: u8 = 2;
+/// Delay before a raised or removed spend limit takes effect.
+pub const LIMIT_COOLING_OFF: u64 = 7 * 86_400;
+
+/// EIP-712 domain of signed stake orders.
+pub const EIP712_DOMAIN_TYPE: &str =
+    "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+/// EIP-712 type of a signed stake order.
+pub const STAKE_ORDER_TYPE: &str =
+    "StakeOrder(string event_id,uint8 team,uint256 amount,uint256 nonce,uint64 deadline,address player)";
+/// EIP-712 domain name.
+pub const EIP712_NAME: &str = "Arenaton StakeEngine";
+/// EIP-712 domain version.
+pub const EIP712_VERSION: &str = "1";
+/// ecrecover precompile.
+const ECRECOVER: Address = address!("0000000000000000000000000000000000000001");
+/// Half of the secp256k1 curve order; signatures with a larger `s` are rejected.
+const SECP256K1_HALF_ORDER: U256 = U256::from_limbs([
+    0xdfe92f46681b20a0,
+    0x5d576e7357a4501d,
+    0xffffffffffffffff,
+    0x7fffffffffffffff,
+]);
+
+/// Settled position won by the player.
+pub const OUTCOME_WON: u8 = 1;
+/// Settled position lost by the player.
+pub const OUTCOME_LOST: u8 = 2;
+/// Position refunded on a tie or a canceled event.
+pub const OUTCOME_REFUNDED: u8 = 3;
+
+/// Number of players ranked on a season leaderboard.
+pub const LEADERBOARD_SIZE: usize = 10;
+
+/// Signed stake order as (event id, team, amount, nonce, deadline, player)
+pub type StakeOrder = (String, u8, U256, U256, u64, Address);
+/// (player, amount, potential payout, status, collateral, legs as (event id, team, odds))
+pub type ParlayTicketView = (Address, U256, U256, u8, Address, Vec<(String, u8, U256)>);
+/// (stakes as (player, amount, team, timestamp), total count, next offset or zero on the last page)
+pub type StakesPage = (Vec<(Address, U256, u8, u64)>, u64, u64);
+/// (events as (event id, start date, status, pool 1, pool 2, winner), total count, next offset or zero on the last page)
+pub type PlayerEventsPage = (Vec<(String, u64, u8, U256, U256, u8)>, u64, u64);
+/// (event id, team, stake, outcome, payout, commission, settlement timestamp)
+pub type HistoryEntryView = (String, u8, U256, u8, U256, U256, u64);
+/// (wins, losses, refunds, total staked, total paid out, commission paid, net profit and loss)
+pub type PlayerRecordView = (u64, u64, u64, U256, U256, U256, I256);
+/// (volume, net profit, ROI in `PCT_DENOM` units, wins, losses, current win streak, best win streak)
+pub type SeasonStatsView = (U256, I256, I256, u64, u64, u32, u32);
+/// (player, net profit, volume)
+pub type LeaderboardEntry = (Address, I256, U256);
+
 // Define the main storage structure and its components
 sol_storage! {
     #[entrypoint]
     pub struct StakeEngine {
         mapping(bytes8 => Event) events; // Mapping for storing events
         mapping(bytes8 => Stake[]) stakes; // Mapping for storing stakes per event
-        mapping(address => bytes8[]) player_events; // Mapping of player addresses to their participated events
+        mapping(address => EventIdSet) player_events; // Indexed set of the open positions of each player
 
         address aton_address; // Address of the ATON contract
         address vault_address; // Address of the vault contract
         address core_address; // Address of the core contract
+        address _owner; // Owner allowed to manage collaterals
+        address oracle_address; // Oracle allowed to manage stake limits
+
+        StakeLimits default_limits; // Limits of events without their own
+
+        address bet_slip_address; // Bet slip contract minting positions as NFTs (zero disables slips)
+        mapping(uint256 => SlipPosition) slip_positions; // Position represented by each slip
+
+        mapping(uint256 => ParlayTicket) parlays; // Parlay tickets by id (ids start at 1)
+        uint256 parlay_count; // Number of parlay tickets placed
+        mapping(address => uint256[]) player_parlays; // Parlay tickets of each player
+
+        mapping(uint256 => ScoreMarket) markets; // Score markets by id (ids start at 1)
+        uint256 market_count; // Number of score markets created
+
+        mapping(uint256 => Outright) outrights; // Outright markets by id (ids start at 1)
+        uint256 outright_count; // Number of outright markets created
+
+        mapping(address => mapping(uint256 => bool)) used_nonces; // Stake order nonces filled or canceled by each player
+
+        mapping(address => address) referrers; // Referrer bound to each player
+        mapping(address => ReferrerStats) referrer_stats; // Volume and rewards of each referrer
+        uint256 referral_share; // Share of a referred player's commission paid to the referrer, in `PCT_DENOM` units
+
+        mapping(address => bool) promoters; // Accounts allowed to issue promo credits
+        mapping(address => PromoCredit[]) promo_credits; // Unspent promo credits of each player
+        uint256 promo_reserve; // ATON backing promo credits (issued or staked)
+        uint256 promo_outstanding; // Unspent, unexpired promo credit
+        uint256 promo_credit_cap; // Maximum outstanding promo credit
+
+        mapping(address => bool) liquidity_providers; // Accounts allowed to seed event pools
+        mapping(address => LiquidityStats) liquidity_stats; // House liquidity seeded and returned per provider
+        uint256 liquidity_deployed; // ATON seeded into events not yet withdrawn, kept apart from player stakes
+
+        mapping(address => PlayerControls) player_controls; // Self-imposed spend limits and exclusion of each player
+
+        mapping(address => HistoryEntry[]) player_history; // Settled positions of each player, oldest first
+        mapping(address => PlayerRecord) player_records; // Aggregated results of each player
+
+        uint256 season; // The season in progress
+        mapping(uint256 => Season) seasons; // Results and leaderboard of each season
+
+        mapping(address => CollateralPool) collateral_pools; // Accounting per collateral token
+        address[] collateral_list; // Every collateral token ever allowed
+    }
+
+    /// Accounting of a collateral token accepted for stakes
+    pub struct CollateralPool {
+        bool allowed; // Whether new events and stakes may use this collateral.
+        bool listed; // Whether the token is already present in `collateral_list`.
+        uint256 total_staked; // Total amount staked in this collateral.
+        uint256 total_paid; // Total amount paid out to players in this collateral.
+        uint256 commission; // Total commission retained in this collateral.
+        uint256 parlay_reserve; // Collateral backing parlay payouts (house funds plus open stakes).
+        uint256 parlay_liability; // Potential payout of every open parlay ticket.
+    }
+
+    /// Accumulator ticket combining outcomes of several events
+    pub struct ParlayTicket {
+        address player; // The player who placed the ticket.
+        address collateral; // Collateral of every leg.
+        uint256 amount; // The amount staked.
+        uint256 potential_payout; // Payout if every leg wins, locked at placement.
+        uint8 status; // 0 = none, 1 = open, 2 = settled.
+        ParlayLeg[] legs; // The combined outcomes.
+    }
+
+    /// House liquidity seeded into event pools by a provider, in ATON
+    pub struct LiquidityStats {
+        uint256 seeded; // Total ATON seeded.
+        uint256 returned; // Total ATON withdrawn after settlement.
+    }
+
+    /// Liquidity a provider seeded into an event
+    pub struct LiquiditySeed {
+        uint256[2] amounts; // Seed on each team.
+        bool withdrawn; // Whether the provider's share was returned.
+    }
+
+    /// Free-bet credit in ATON, stakeable until it expires
+    pub struct PromoCredit {
+        uint256 amount; // Unspent credit.
+        uint64 expires_at; // The credit cannot be staked from this timestamp.
+    }
+
+    /// Attribution and rewards of a referrer, in ATON
+    pub struct ReferrerStats {
+        uint256 referrals; // Players bound to the referrer.
+        uint256 volume; // ATON staked on events by referred players.
+        uint256 earned; // Rewards accrued from the commission of referred players.
+        uint256 claimed; // Rewards already claimed.
+        uint256 pending; // Rewards accrued on events that have not settled yet.
+    }
+
+    /// Responsible gambling controls set by a player for themselves
+    pub struct PlayerControls {
+        uint64 excluded_until; // No new stakes are accepted before this timestamp.
+        mapping(address => SpendLimits) limits; // Spend limits by collateral token.
+    }
+
+    /// Daily, weekly and monthly spend limits of a player in one collateral
+    pub struct SpendLimits {
+        SpendLimit daily;
+        SpendLimit weekly;
+        SpendLimit monthly;
+    }
+
+    /// Spend limit over fixed windows of one period (zero means no limit)
+    pub struct SpendLimit {
+        uint256 limit; // Limit currently in force.
+        uint256 pending_limit; // Raised or removed limit waiting for its cooling-off.
+        uint64 pending_at; // When `pending_limit` takes effect (zero when none is pending).
+        uint64 window; // Index of the window `spent` belongs to.
+        uint256 spent; // Amount staked during `window`.
+    }
+
+    /// Settled position in the betting history of a player
+    pub struct HistoryEntry {
+        bytes8 event_id; // The settled event.
+        uint8 team; // The team the player bet on.
+        uint256 stake; // The total amount staked.
+        uint8 outcome; // `OUTCOME_WON`, `OUTCOME_LOST` or `OUTCOME_REFUNDED`.
+        uint256 payout; // The amount paid out.
+        uint256 commission; // The commission taken from the winnings.
+        uint64 settled_at; // Timestamp of the settlement.
+    }
+
+    /// Aggregated results of a player over every settled position
+    pub struct PlayerRecord {
+        uint64 wins; // Positions won.
+        uint64 losses; // Positions lost.
+        uint64 refunds; // Positions refunded.
+        uint256 total_staked; // Amount staked on settled positions.
+        uint256 total_payout; // Amount paid out.
+        uint256 total_commission; // Commission taken from the winnings.
+    }
+
+    /// Tipster competition over the ATON positions settled while it is in progress
+    pub struct Season {
+        uint64 started_at; // Timestamp the season was rolled in (zero for the first one).
+        uint64 ended_at; // Timestamp the next season was rolled in (zero while in progress).
+        mapping(address => SeasonStats) stats; // Results of each player.
+        address[] leaderboard; // Top `LEADERBOARD_SIZE` players by net profit, best first.
+    }
+
+    /// Results of a player in a season
+    pub struct SeasonStats {
+        uint256 volume; // Amount staked on won and lost positions.
+        uint256 payout; // Amount won.
+        uint64 wins; // Positions won.
+        uint64 losses; // Positions lost.
+        uint32 streak; // Consecutive wins up to the last settled position.
+        uint32 best_streak; // Longest run of consecutive wins.
+    }
+
+    /// Parimutuel market resolved from the final scores of an event
+    pub struct ScoreMarket {
+        bytes8 event_id; // The event the market is resolved from.
+        address collateral; // Collateral of the market, fixed at creation.
+        uint8 kind; // `MARKET_TOTALS`, `MARKET_SPREAD` or `MARKET_EXACT_SCORE`.
+        int64 line; // Totals or handicap line, scaled by `LINE_SCALE`.
+        uint8 status; // 0 = none, 1 = open, 2 = resolved, 3 = void (every stake refunded).
+        uint32 winning_outcome; // The winning outcome once resolved.
+        uint256 total; // Total staked on every outcome.
+        uint256 commission; // Commission retained at resolution.
+        mapping(uint32 => uint256) pools; // Total staked on each outcome.
+        mapping(address => MarketPosition) positions; // Position of each player.
+    }
+
+    /// Position of a player in a score market
+    pub struct MarketPosition {
+        uint32 outcome; // The outcome backed by the player.
+        uint256 amount; // The amount staked.
+        bool paid; // Whether the position has been paid.
+    }
+
+    /// Parimutuel futures market with up to `MAX_OUTRIGHT_OUTCOMES` labelled outcomes
+    pub struct Outright {
+        address collateral; // Collateral of the market.
+        uint64 close_time; // Staking closes at this timestamp.
+        uint8 status; // 0 = none, 1 = open, 2 = settled, 3 = void (every stake refunded).
+        uint8 funded_winners; // Winning outcomes with stakes, sharing the pool in a dead heat.
+        uint256 total; // Total staked on every outcome.
+        uint256 commission; // Commission retained at settlement.
+        string[] labels; // Label of each outcome, indexed by outcome.
+        uint256[] pools; // Total staked on each outcome.
+        uint8[] winners; // Winning outcomes, more than one on a dead heat.
+        mapping(address => OutrightPosition) positions; // Position of each player.
+    }
+
+    /// Stakes of a player across the outcomes of an outright
+    pub struct OutrightPosition {
+        mapping(uint8 => uint256) stakes; // Amount staked on each outcome.
+        uint256 total; // Amount staked on every outcome.
+        bool paid; // Whether the position has been paid.
+    }
+
+    /// Outcome of a single event within a parlay ticket
+    pub struct ParlayLeg {
+        bytes8 event_id; // The event of the leg.
+        uint8 team; // The outcome backed by the leg.
+        uint256 odds; // Decimal odds at placement, scaled by `ODDS_PRECISION`.
+    }
+
+    /// Stake limits of an event, in collateral units (zero disables a limit)
+    pub struct StakeLimits {
+        bool configured; // Whether the event overrides the default limits.
+        uint256 min_stake; // Minimum amount of a single stake.
+        uint256 max_player_stake; // Maximum total stake of a player in the event.
+        uint256 max_outcome_pool; // Maximum pool of a single outcome.
+        uint256 max_imbalance; // Maximum difference between the two outcome pools.
+    }
+
+    /// Position of a player in an event represented by a bet slip
+    pub struct SlipPosition {
+        bytes8 event_id; // The event of the position.
+        address player; // The player who staked.
+    }
+
+    /// Structure representing a player's stake in an event
+    ///
+    /// Slot map (2 slots, fields packed from the low-order bytes of a word):
+    /// * slot 0: `player` (bytes 0-19), `team` (byte 20), `timestamp` (bytes 21-28)
+    /// * slot 1: `amount`
+    pub struct Stake {
+        address player; // The player who placed the stake.
+        uint8 team; // The team the player is betting on.
+        uint64 timestamp; // Timestamp of the stake.
+        uint256 amount; // The total amount of tokens staked by the player.
+    }
+
+    /// Structure representing a player in an event
+    ///
+    /// Slot map (2 slots, fields packed from the low-order bytes of a word):
+    /// * slot 0: `stake` (bytes 0-15), `team` (byte 16), `paid` (byte 17), `credit_stake` (bytes 18-29)
+    /// * slot 1: `slip_id`
+    pub struct Player {
+        uint128 stake; // The total amount of tokens staked by the player.
+        uint8 team; // The team the player is betting on.
+        bool paid; // Whether the player has been paid their reward.
+        uint96 credit_stake; // Part of `stake` funded with promo credits, never paid back to the player.
+        uint256 slip_id; // Bet slip of the position (zero when none was minted).
+    }
+
+    /// Structure representing an event for betting
+    pub struct Event {
+        uint64 start_date; // The start date and time of the event.
+        mapping(address => Player) player; // Mapping of players in the event.
+        uint256[2] total; // Total stakes for each team.
+        uint8 winner; // The winner of the event.
+        uint8 status; // Status of the event (e.g., unopen, open, closed, paid).
+        address collateral; // Collateral token of the event (zero means ATON).
+        bool commission_accrued; // Whether the event commission was added to its collateral pool.
+        StakeLimits limits; // Limits overriding the default ones.
+        uint256[2] seeded; // House liquidity included in `total`, per team.
+        mapping(address => LiquiditySeed) liquidity; // House liquidity of each provider.
+        uint256 bonus; // Sponsored ATON shared by the winners on top of their payout.
+        mapping(address => uint256) sponsors; // Bonus deposited by each sponsor, refundable on a void.
+        mapping(address => uint256) referral_rewards; // Rewards accrued by each referrer, held until settlement.
+    }
+}
+
+// Implementation of the StakeEngine functionality
+#[public]
+impl StakeEngine {
+    /// Initialize the StakeEngine with the required addresses
+    pub fn initialize(
+        &mut self,
+        _aton_address: Address,
+        _core_address: Address,
+    ) -> Result<bool, Error> {
+        if self.aton_address.get() != Address::ZERO {
+            return Err(Error::AlreadyInitialized(AlreadyInitialized {}));
+        }
+
+        self._owner.set(msg::sender());
+
+        let aton_contract = IATON::new(_aton_address);
+        let config = Call::new_in(self);
+
+        let _vault_address = aton_contract
+            .vault(config)
+            .map_err(|_| Error::AtonCallFailed(AtonCallFailed { aton: _aton_address }))?;
+
+        self._set_vault_aton(_aton_address, _vault_address);
+        self.core_address.set(_core_address);
+
+        // ATON is always accepted as collateral.
+        self._set_collateral(_aton_address, true);
+
+        Ok(true)
+    }
+
+    /// Returns the owner of the StakeEngine
+    pub fn owner(&self) -> Address {
+        self._owner.get()
+    }
+
+    /// Allow or disallow a collateral token for new events and stakes (owner only).
+    /// Disallowing a token never blocks payouts of events already using it.
+    pub fn set_collateral(&mut self, token: Address, allowed: bool) -> Result<bool, Error> {
+        self.only_owner()?;
+
+        if token == Address::ZERO {
+            return Err(Error::InvalidAddress(InvalidAddress { account: token }));
+        }
+
+        self._set_collateral(token, allowed);
+        evm::log(CollateralUpdated { token, allowed });
+
+        Ok(true)
+    }
+
+    /// Retrieve every collateral token as (token, allowed, total staked, total paid, commission)
+    pub fn get_collaterals(&self) -> Vec<(Address, bool, U256, U256, U256)> {
+        let mut result = Vec::with_capacity(self.collateral_list.len());
+
+        for i in 0..self.collateral_list.len() {
+            if let Some(token) = self.collateral_list.get(i) {
+                let pool = self.collateral_pools.get(token);
+                result.push((
+                    token,
+                    pool.allowed.get(),
+                    pool.total_staked.get(),
+                    pool.total_paid.get(),
+                    pool.commission.get(),
+                ));
+            }
+        }
+
+        result
+    }
+
+    /// Choose the collateral token of an event before its first stake (owner only)
+    pub fn set_event_collateral(&mut self, _event_id: String, token: Address) -> Result<bool, Error> {
+        self.only_owner()?;
+
+        if !self.collateral_pools.get(token).allowed.get() {
+            return Err(Error::CollateralNotAllowed(CollateralNotAllowed { token }));
+        }
+
+        let event_id_bytes = string_to_bytes8(&_event_id);
+        let mut event = self.events.setter(event_id_bytes);
+
+        // The collateral is locked once the event holds stakes.
+        if event.total.get(0).unwrap_or_default() != U256::ZERO
+            || event.total.get(1).unwrap_or_default() != U256::ZERO
+        {
+            return Err(Error::CollateralLocked(CollateralLocked {
+                event_id: event_id_bytes,
+            }));
+        }
+
+        event.collateral.set(token);
+        evm::log(EventCollateralSet {
+            event_id: event_id_bytes,
+            token,
+        });
+
+        Ok(true)
+    }
+
+    /// Retrieve the collateral token of an event
+    pub fn event_collateral(&self, _event_id: String) -> Address {
+        self._event_collateral(string_to_bytes8(&_event_id))
+    }
+
+    /// Set the oracle allowed to manage stake limits (owner only)
+    pub fn set_oracle(&mut self, _oracle_address: Address) -> Result<bool, Error> {
+        self.only_owner()?;
+        self.oracle_address.set(_oracle_address);
+        Ok(true)
+    }
+
+    /// Returns the oracle of the StakeEngine
+    pub fn oracle(&self) -> Address {
+        self.oracle_address.get()
+    }
+
+    /// Set the limits applied to events without their own (owner or oracle only)
+    pub fn set_default_stake_limits(
+        &mut self,
+        min_stake: U256,
+        max_player_stake: U256,
+        max_outcome_pool: U256,
+        max_imbalance: U256,
+    ) -> Result<bool, Error> {
+        self.only_owner_or_oracle()?;
+
+        StakeEngine::_set_limits(
+            &mut self.default_limits,
+            min_stake,
+            max_player_stake,
+            max_outcome_pool,
+            max_imbalance,
+        );
+
+        evm::log(StakeLimitsUpdated {
+            event_id: FixedBytes::<8>::ZERO,
+            min_stake,
+            max_player_stake,
+            max_outcome_pool,
+            max_imbalance,
+        });
+
+        Ok(true)
+    }
+
+    /// Set the limits of an event, overriding the default ones (owner or oracle only)
+    pub fn set_event_stake_limits(
+        &mut self,
+        _event_id: String,
+        min_stake: U256,
+        max_player_stake: U256,
+        max_outcome_pool: U256,
+        max_imbalance: U256,
+    ) -> Result<bool, Error> {
+        self.only_owner_or_oracle()?;
+
+        let event_id_bytes = string_to_bytes8(&_event_id);
+        let mut event = self.events.setter(event_id_bytes);
+        StakeEngine::_set_limits(
+            &mut event.limits,
+            min_stake,
+            max_player_stake,
+            max_outcome_pool,
+            max_imbalance,
+        );
+
+        evm::log(StakeLimitsUpdated {
+            event_id: event_id_bytes,
+            min_stake,
+            max_player_stake,
+            max_outcome_pool,
+            max_imbalance,
+        });
+
+        Ok(true)
+    }
+
+    /// Drop the limits of an event so the default ones apply again (owner or oracle only)
+    pub fn clear_event_stake_limits(&mut self, _event_id: String) -> Result<bool, Error> {
+        self.only_owner_or_oracle()?;
+
+        let mut event = self.events.setter(string_to_bytes8(&_event_id));
+        event.limits.configured.set(false);
+
+        Ok(true)
+    }
+
+    /// Limits applied to an event as (min stake, max player stake, max outcome pool, max imbalance)
+    pub fn stake_limits(&self, _event_id: String) -> (U256, U256, U256, U256) {
+        self._stake_limits(string_to_bytes8(&_event_id))
+    }
+
+    /// Largest amount `player` can currently stake on `_team` (`U256::MAX` when unbounded)
+    pub fn max_stake(&self, _event_id: String, player: Address, _team: u8) -> U256 {
+        let event_id_bytes = string_to_bytes8(&_event_id);
+        if _team != 1 && _team != 2 {
+            return U256::ZERO;
+        }
+
+        let (_, max_player_stake, max_outcome_pool, max_imbalance) =
+            self._stake_limits(event_id_bytes);
+        let event = self.events.get(event_id_bytes);
+        let side = (_team - 1) as usize;
+        let pool = event.total.get(side).unwrap_or_default();
+        let other_pool = event.total.get(1 - side).unwrap_or_default();
+
+        // A player cannot switch sides once staked.
+        let player_data = event.player.get(player);
+        let player_stake = player_data.stake.get().to::<U256>();
+        if player_stake != U256::ZERO && player_data.team.get() != Uint::<8, 1>::from(_team) {
+            return U256::ZERO;
+        }
+
+        let mut available = U256::MAX;
+        if max_player_stake != U256::ZERO {
+            available = available.min(max_player_stake.saturating_sub(player_stake));
+        }
+        if max_outcome_pool != U256::ZERO {
+            available = available.min(max_outcome_pool.saturating_sub(pool));
+        }
+        if max_imbalance != U256::ZERO {
+            available = available.min((other_pool + max_imbalance).saturating_sub(pool));
+        }
+
+        available
+    }
+
+    /// Set the bet slip contract minting stake positions (owner only, zero disables slips)
+    pub fn set_bet_slip(&mut self, _bet_slip: Address) -> Result<bool, Error> {
+        self.only_owner()?;
+
+        self.bet_slip_address.set(_bet_slip);
+        evm::log(BetSlipUpdated {
+            bet_slip: _bet_slip,
+        });
+
+        Ok(true)
+    }
+
+    /// Returns the bet slip contract
+    pub fn bet_slip(&self) -> Address {
+        self.bet_slip_address.get()
+    }
+
+    /// Position represented by a bet slip as (event id, player who staked)
+    pub fn slip_position(&self, token_id: U256) -> (String, Address) {
+        let position = self.slip_positions.get(token_id);
+        (bytes8_to_string(position.event_id.get()), position.player.get())
+    }
+
+    /// Pay the position of a bet slip to its current holder
+    pub fn pay_slip(&mut self, token_id: U256) -> Result<bool, Error> {
+        let position = self.slip_positions.get(token_id);
+        let event_id = bytes8_to_string(position.event_id.get());
+        let player = position.player.get();
+        if player == Address::ZERO {
+            return Err(Error::UnknownSlip(UnknownSlip { token_id }));
+        }
+
+        self._pay_event(event_id, player)
+    }
+
+    /// Place a parlay ticket combining 2 to `MAX_PARLAY_LEGS` outcomes of distinct events
+    ///
+    /// Each leg is priced at the implied odds of its pool at placement and the ticket pays
+    /// `amount` times the product of those odds if every leg wins. Parlay stakes do not enter
+    /// the event pools; payouts come from the parlay reserve of the collateral, which must
+    /// cover the ticket. Reverts with `SlippageExceeded` if the potential payout is below
+    /// `_min_payout` (zero disables the check).
+    #[payable]
+    pub fn place_parlay(
+        &mut self,
+        _event_ids: Vec<String>,
+        _teams: Vec<u8>,
+        _amount: U256,
+        _min_payout: U256,
+    ) -> Result<U256, Error> {
+        let legs: Vec<FixedBytes<8>> = _event_ids.iter().map(|id| string_to_bytes8(id)).collect();
+        if legs.len() < 2 || legs.len() > MAX_PARLAY_LEGS || legs.len() != _teams.len() {
+            return Err(Error::InvalidLegCount(InvalidLegCount {
+                count: U256::from(legs.len()),
+            }));
+        }
+
+        let collateral = self._event_collateral(legs[0]);
+        if !self.collateral_pools.get(collateral).allowed.get() {
+            return Err(Error::CollateralNotAllowed(CollateralNotAllowed { token: collateral }));
+        }
+
+        let mut odds = Vec::with_capacity(legs.len());
+        for (i, (&event_id, &team)) in legs.iter().zip(_teams.iter()).enumerate() {
+            if legs[..i].contains(&event_id) {
+                return Err(Error::DuplicateLeg(DuplicateLeg { event_id }));
+            }
+            if self._event_collateral(event_id) != collateral {
+                return Err(Error::MixedCollateral(MixedCollateral {
+                    event_id,
+                    collateral: self._event_collateral(event_id),
+                }));
+            }
+            self._can_stake_event(event_id)?;
+            odds.push(self._leg_odds(event_id, team)?);
+        }
+
+        let amount = self._collect_collateral(collateral, msg::sender(), msg::value(), _amount)?;
+        self._charge_player_controls(msg::sender(), collateral, amount)?;
+        let potential_payout = StakeEngine::_parlay_payout(amount, &odds);
+        if potential_payout < _min_payout {
+            return Err(Error::SlippageExceeded(SlippageExceeded {
+                multiplier: potential_payout,
+                min_multiplier: _min_payout,
+            }));
+        }
+
+        // The reserve, including this stake, must cover every open ticket.
+        let mut pool = self.collateral_pools.setter(collateral);
+        let reserve = pool.parlay_reserve.get() + amount;
+        let liability = pool.parlay_liability.get() + potential_payout;
+        if reserve < liability {
+            return Err(Error::InsufficientParlayReserve(InsufficientParlayReserve {
+                available: reserve.saturating_sub(pool.parlay_liability.get()),
+                needed: potential_payout,
+            }));
+        }
+        pool.parlay_reserve.set(reserve);
+        pool.parlay_liability.set(liability);
+        let total_staked = pool.total_staked.get();
+        pool.total_staked.set(total_staked + amount);
+
+        let ticket_id = self.parlay_count.get() + U256::from(1);
+        self.parlay_count.set(ticket_id);
+
+        let player = msg::sender();
+        let mut ticket = self.parlays.setter(ticket_id);
+        ticket.player.set(player);
+        ticket.collateral.set(collateral);
+        ticket.amount.set(amount);
+        ticket.potential_payout.set(potential_payout);
+        ticket.status.set(Uint::<8, 1>::from(1u8));
+        for (i, &event_id) in legs.iter().enumerate() {
+            let mut leg = ticket.legs.grow();
+            leg.event_id.set(event_id);
+            leg.team.set(Uint::<8, 1>::from(_teams[i]));
+            leg.odds.set(odds[i]);
+        }
+        self.player_parlays.setter(player).push(ticket_id);
+
+        evm::log(ParlayPlaced {
+            ticket_id,
+            player,
+            amount,
+            potential_payout,
+        });
+
+        Ok(ticket_id)
+    }
+
+    /// Settle a parlay ticket once every leg is closed
+    ///
+    /// A lost leg loses the ticket. Voided legs (tie or canceled) are dropped and the ticket
+    /// pays at the product of the remaining odds; if every leg is voided the stake is refunded.
+    pub fn settle_parlay(&mut self, ticket_id: U256) -> Result<U256, Error> {
+        let ticket = self.parlays.get(ticket_id);
+        if ticket.status.get() != Uint::<8, 1>::from(1u8) {
+            return Err(Error::ParlayNotOpen(ParlayNotOpen { ticket_id }));
+        }
+
+        let mut lost = false;
+        let mut odds = Vec::with_capacity(ticket.legs.len());
+        for i in 0..ticket.legs.len() {
+            let leg = ticket.legs.get(i).unwrap();
+            let event_id = leg.event_id.get();
+            let event = self.events.get(event_id);
+            let status = event.status.get();
+            if status != Uint::<8, 1>::from(2u8) {
+                return Err(Error::WrongStatus(WrongStatus {
+                    event_id,
+                    status: status.to::<u8>(),
+                    expected: 2,
+                }));
+            }
+
+            let winner = event.winner.get();
+            if winner == Uint::<8, 1>::from(0u8) || winner == Uint::<8, 1>::from(3u8) {
+                continue;
+            }
+            if winner != leg.team.get() {
+                lost = true;
+            }
+            odds.push(leg.odds.get());
+        }
+
+        let player = ticket.player.get();
+        let collateral = ticket.collateral.get();
+        let amount = ticket.amount.get();
+        let potential_payout = ticket.potential_payout.get();
+        // Dropping a voided leg priced under 1.0 raises the product of the others, but the
+        // ticket never pays more than the reserve set aside for it.
+        let payout = if lost {
+            U256::ZERO
+        } else {
+            StakeEngine::_parlay_payout(amount, &odds).min(potential_payout)
+        };
+
+        let mut pool = self.collateral_pools.setter(collateral);
+        let reserve = pool.parlay_reserve.get();
+        let liability = pool.parlay_liability.get();
+        let (Some(reserve), Some(liability)) = (
+            reserve.checked_sub(payout),
+            liability.checked_sub(potential_payout),
+        ) else {
+            return Err(Error::InsufficientParlayReserve(InsufficientParlayReserve {
+                available: reserve,
+                needed: payout,
+            }));
+        };
+        pool.parlay_reserve.set(reserve);
+        pool.parlay_liability.set(liability);
+        let total_paid = pool.total_paid.get();
+        pool.total_paid.set(total_paid + payout);
+
+        self.parlays
+            .setter(ticket_id)
+            .status
+            .set(Uint::<8, 1>::from(2u8));
+        // A ticket whose legs were all voided just returns the stake.
+        let outcome = if lost {
+            OUTCOME_LOST
+        } else if odds.is_empty() {
+            OUTCOME_REFUNDED
+        } else {
+            OUTCOME_WON
+        };
+        self._record_result(player, amount, outcome, payout, U256::ZERO);
+
+        if payout > U256::ZERO {
+            self._transfer_collateral(collateral, player, payout)?;
+        }
+
+        evm::log(ParlaySettled {
+            ticket_id,
+            player,
+            payout,
+        });
+
+        Ok(payout)
+    }
+
+    /// Retrieve a parlay ticket
+    pub fn get_parlay(&self, ticket_id: U256) -> ParlayTicketView {
+        let ticket = self.parlays.get(ticket_id);
+        let mut legs = Vec::with_capacity(ticket.legs.len());
+        for i in 0..ticket.legs.len() {
+            if let Some(leg) = ticket.legs.get(i) {
+                legs.push((
+                    bytes8_to_string(leg.event_id.get()),
+                    leg.team.get().to::<u8>(),
+                    leg.odds.get(),
+                ));
+            }
+        }
+
+        (
+            ticket.player.get(),
+            ticket.amount.get(),
+            ticket.potential_payout.get(),
+            ticket.status.get().to::<u8>(),
+            ticket.collateral.get(),
+            legs,
+        )
+    }
+
+    /// Retrieve the parlay ticket ids of a player
+    pub fn get_player_parlays(&self, player: Address) -> Vec<U256> {
+        let tickets = self.player_parlays.get(player);
+        (0..tickets.len()).filter_map(|i| tickets.get(i)).collect()
+    }
+
+    /// Parlay reserve of a collateral as (reserve, liability of open tickets)
+    pub fn parlay_reserve(&self, token: Address) -> (U256, U256) {
+        let pool = self.collateral_pools.get(token);
+        (pool.parlay_reserve.get(), pool.parlay_liability.get())
+    }
+
+    /// Add collateral to the parlay reserve backing parlay payouts
+    ///
+    /// ETH is minted into ATON for the ATON reserve; other collaterals are pulled with `transferFrom`.
+    #[payable]
+    pub fn fund_parlay_reserve(&mut self, token: Address, amount: U256) -> Result<bool, Error> {
+        if !self.collateral_pools.get(token).allowed.get() {
+            return Err(Error::CollateralNotAllowed(CollateralNotAllowed { token }));
+        }
+
+        let amount = self._collect_collateral(token, msg::sender(), msg::value(), amount)?;
+        let mut pool = self.collateral_pools.setter(token);
+        let reserve = pool.parlay_reserve.get() + amount;
+        pool.parlay_reserve.set(reserve);
+
+        evm::log(ParlayReserveUpdated { token, reserve });
+
+        Ok(true)
+    }
+
+    /// Withdraw parlay reserve not backing open tickets (owner only)
+    pub fn withdraw_parlay_reserve(
+        &mut self,
+        token: Address,
+        amount: U256,
+        to: Address,
+    ) -> Result<bool, Error> {
+        self.only_owner()?;
+
+        let mut pool = self.collateral_pools.setter(token);
+        let reserve = pool.parlay_reserve.get();
+        let available = reserve.saturating_sub(pool.parlay_liability.get());
+        if amount > available {
+            return Err(Error::InsufficientParlayReserve(InsufficientParlayReserve {
+                available,
+                needed: amount,
+            }));
+        }
+        pool.parlay_reserve.set(reserve - amount);
+
+        self._transfer_collateral(token, to, amount)?;
+        evm::log(ParlayReserveUpdated {
+            token,
+            reserve: reserve - amount,
+        });
+
+        Ok(true)
+    }
+
+    /// Create a score market on an event (owner or oracle only)
+    ///
+    /// `line` is the totals line for `MARKET_TOTALS` and the handicap added to Team A for
+    /// `MARKET_SPREAD`, both scaled by `LINE_SCALE`; it is ignored for `MARKET_EXACT_SCORE`.
+    /// The event must be open in CoreEvents and not started yet.
+    pub fn create_market(&mut self, _event_id: String, kind: u8, line: i64) -> Result<U256, Error> {
+        self.only_owner_or_oracle()?;
+
+        if !(MARKET_TOTALS..=MARKET_EXACT_SCORE).contains(&kind) {
+            return Err(Error::InvalidMarketKind(InvalidMarketKind { kind }));
+        }
+
+        self._read_event_core(&_event_id)?;
+        self._create_market(string_to_bytes8(&_event_id), kind, line)
+    }
+
+    /// Stake on an outcome of a score market before its event starts
+    ///
+    /// Outcomes are 1 or 2 for totals and spreads, and `home << 16 | away` for exact scores.
+    #[payable]
+    pub fn stake_market(&mut self, market_id: U256, outcome: u32, _amount: U256) -> Result<bool, Error> {
+        let market = self.markets.get(market_id);
+        let status = market.status.get().to::<u8>();
+        if status != 1 {
+            return Err(Error::MarketWrongStatus(MarketWrongStatus { market_id, status }));
+        }
+
+        let kind = market.kind.get().to::<u8>();
+        if kind != MARKET_EXACT_SCORE && outcome != 1 && outcome != 2 {
+            return Err(Error::InvalidOutcome(InvalidOutcome { outcome }));
+        }
+
+        let player = msg::sender();
+        let position = market.positions.get(player);
+        let previous_outcome = position.outcome.get().to::<u32>();
+        if position.amount.get() != U256::ZERO && previous_outcome != outcome {
+            return Err(Error::OutcomeLocked(OutcomeLocked {
+                outcome: previous_outcome,
+            }));
+        }
+
+        let event_id_bytes = market.event_id.get();
+        let collateral = market.collateral.get();
+        self._can_stake_event(event_id_bytes)?;
+
+        let amount = self._collect_collateral(collateral, msg::sender(), msg::value(), _amount)?;
+        self._charge_player_controls(msg::sender(), collateral, amount)?;
+
+        let mut market = self.markets.setter(market_id);
+        let total = market.total.get();
+        market.total.set(total + amount);
+        let mut pool = market.pools.setter(Uint::<32, 1>::from(outcome));
+        let pool_total = pool.get();
+        pool.set(pool_total + amount);
+        let mut position = market.positions.setter(player);
+        let position_amount = position.amount.get();
+        position.amount.set(position_amount + amount);
+        position.outcome.set(Uint::<32, 1>::from(outcome));
+
+        let mut collateral_pool = self.collateral_pools.setter(collateral);
+        let total_staked = collateral_pool.total_staked.get();
+        collateral_pool.total_staked.set(total_staked + amount);
+
+        evm::log(MarketStake {
+            market_id,
+            player,
+            outcome,
+            amount,
+        });
+
+        Ok(true)
+    }
+
+    /// Resolve a score market from the final scores recorded in CoreEvents
+    ///
+    /// Canceled events, events closed without scores, pushes on the line and markets without
+    /// a winning stake are voided and every stake is refunded.
+    pub fn resolve_market(&mut self, market_id: U256) -> Result<bool, Error> {
+        let market = self.markets.get(market_id);
+        let status = market.status.get().to::<u8>();
+        if status != 1 {
+            return Err(Error::MarketWrongStatus(MarketWrongStatus { market_id, status }));
+        }
+        let event_id_bytes = market.event_id.get();
+        let event_id = bytes8_to_string(event_id_bytes);
+
+        let core_contract = ICoreEvents::new(self.core_address.get());
+        let (_, event_status, winner) = core_contract
+            .get_event(Call::new_in(self), event_id.clone())
+            .map_err(|_| Error::EventNotFound(EventNotFound { event_id: event_id_bytes }))?;
+        if event_status != 2 {
+            return Err(Error::WrongStatus(WrongStatus {
+                event_id: event_id_bytes,
+                status: event_status,
+                expected: 2,
+            }));
+        }
+        let (has_scores, home_score, away_score) = core_contract
+            .get_event_scores(Call::new_in(self), event_id)
+            .map_err(|_| Error::EventNotFound(EventNotFound { event_id: event_id_bytes }))?;
+
+        let scores = if has_scores && winner != 3 {
+            Some((home_score, away_score))
+        } else {
+            None
+        };
+        self._resolve_market(market_id, scores);
+
+        Ok(true)
+    }
+
+    /// Pay the position of `player` in a resolved or voided score market
+    pub fn claim_market(&mut self, market_id: U256, player: Address) -> Result<U256, Error> {
+        let market = self.markets.get(market_id);
+        let status = market.status.get().to::<u8>();
+        if status != 2 && status != 3 {
+            return Err(Error::MarketWrongStatus(MarketWrongStatus { market_id, status }));
+        }
+
+        let position = market.positions.get(player);
+        let amount = position.amount.get();
+        if amount == U256::ZERO || position.paid.get() {
+            return Err(Error::NothingToClaim(NothingToClaim { market_id, player }));
+        }
+
+        let winning_outcome = market.winning_outcome.get();
+        let payout = if status == 3 {
+            amount
+        } else if position.outcome.get() == winning_outcome {
+            StakeEngine::_parimutuel_payout(
+                amount,
+                market.pools.get(winning_outcome),
+                market.total.get(),
+                market.commission.get(),
+            )
+        } else {
+            U256::ZERO
+        };
+        let outcome = if status == 3 {
+            OUTCOME_REFUNDED
+        } else if payout > U256::ZERO {
+            OUTCOME_WON
+        } else {
+            OUTCOME_LOST
+        };
+        let commission = if outcome == OUTCOME_WON {
+            amount * market.commission.get() / market.pools.get(winning_outcome)
+        } else {
+            U256::ZERO
+        };
+        let collateral = market.collateral.get();
+
+        self.markets
+            .setter(market_id)
+            .positions
+            .setter(player)
+            .paid
+            .set(true);
+
+        let mut pool = self.collateral_pools.setter(collateral);
+        let total_paid = pool.total_paid.get();
+        pool.total_paid.set(total_paid + payout);
+        self._record_result(player, amount, outcome, payout, commission);
+
+        if payout > U256::ZERO {
+            self._transfer_collateral(collateral, player, payout)?;
+        }
+
+        Ok(payout)
+    }
+
+    /// Retrieve a score market as (event id, kind, line, status, winning outcome, total staked)
+    pub fn get_market(&self, market_id: U256) -> (String, u8, i64, u8, u32, U256) {
+        let market = self.markets.get(market_id);
+        (
+            bytes8_to_string(market.event_id.get()),
+            market.kind.get().to::<u8>(),
+            market.line.get().as_i64(),
+            market.status.get().to::<u8>(),
+            market.winning_outcome.get().to::<u32>(),
+            market.total.get(),
+        )
+    }
+
+    /// Total staked on an outcome of a score market
+    pub fn market_pool(&self, market_id: U256, outcome: u32) -> U256 {
+        self.markets
+            .get(market_id)
+            .pools
+            .get(Uint::<32, 1>::from(outcome))
+    }
+
+    /// Position of a player in a score market as (outcome, amount, paid)
+    pub fn market_position(&self, market_id: U256, player: Address) -> (u32, U256, bool) {
+        let market = self.markets.get(market_id);
+        let position = market.positions.get(player);
+        (
+            position.outcome.get().to::<u32>(),
+            position.amount.get(),
+            position.paid.get(),
+        )
+    }
+
+    /// Create an outright market with labelled outcomes (owner or oracle only)
+    ///
+    /// Outcomes are numbered from 0 in the order of `labels`; staking closes at `close_time`.
+    pub fn create_outright(
+        &mut self,
+        collateral: Address,
+        close_time: u64,
+        labels: Vec<String>,
+    ) -> Result<U256, Error> {
+        self.only_owner_or_oracle()?;
+
+        if labels.len() < 2 || labels.len() > MAX_OUTRIGHT_OUTCOMES {
+            return Err(Error::InvalidOutcomeCount(InvalidOutcomeCount {
+                count: U256::from(labels.len()),
+            }));
+        }
+        if !self.collateral_pools.get(collateral).allowed.get() {
+            return Err(Error::CollateralNotAllowed(CollateralNotAllowed { token: collateral }));
+        }
+        let timestamp = block::timestamp();
+        if close_time <= timestamp {
+            return Err(Error::DeadlineExpired(DeadlineExpired {
+                deadline: close_time,
+                timestamp,
+            }));
+        }
+
+        let outright_id = self.outright_count.get() + U256::from(1);
+        self.outright_count.set(outright_id);
+
+        let mut outright = self.outrights.setter(outright_id);
+        outright.collateral.set(collateral);
+        outright.close_time.set(Uint::<64, 1>::from(close_time));
+        outright.status.set(Uint::<8, 1>::from(1u8));
+        for label in labels.iter() {
+            outright.labels.grow().set_str(label);
+            outright.pools.push(U256::ZERO);
+        }
+
+        evm::log(OutrightCreated {
+            outright_id,
+            collateral,
+            close_time,
+            outcome_count: labels.len() as u8,
+        });
+
+        Ok(outright_id)
+    }
+
+    /// Stake on an outcome of an outright before it closes
+    #[payable]
+    pub fn stake_outright(&mut self, outright_id: U256, outcome: u8, _amount: U256) -> Result<bool, Error> {
+        let outright = self.outrights.get(outright_id);
+        let status = outright.status.get().to::<u8>();
+        if status != 1 {
+            return Err(Error::OutrightWrongStatus(OutrightWrongStatus { outright_id, status }));
+        }
+        let close_time = outright.close_time.get().to::<u64>();
+        if block::timestamp() >= close_time {
+            return Err(Error::OutrightClosed(OutrightClosed {
+                outright_id,
+                close_time,
+            }));
+        }
+        if outcome as usize >= outright.pools.len() {
+            return Err(Error::InvalidOutcome(InvalidOutcome {
+                outcome: outcome as u32,
+            }));
+        }
+        let collateral = outright.collateral.get();
+
+        let amount = self._collect_collateral(collateral, msg::sender(), msg::value(), _amount)?;
+        self._charge_player_controls(msg::sender(), collateral, amount)?;
+        self._add_outright_stake(outright_id, msg::sender(), outcome, amount);
+
+        Ok(true)
+    }
+
+    /// Settle an outright with its winning outcomes (owner or oracle only)
+    ///
+    /// Several winners settle as a dead heat: the pool is split equally between the winning
+    /// outcomes that were backed, and each share is paid pro-rata to that outcome's stakes.
+    /// An outright without a backed winner is voided and every stake is refunded. Settlement is
+    /// only possible from `close_time`.
+    pub fn settle_outright(&mut self, outright_id: U256, winners: Vec<u8>) -> Result<bool, Error> {
+        self.only_owner_or_oracle()?;
+        self._settle_outright(outright_id, &winners)
+    }
+
+    /// Void an outright and refund every stake (owner or oracle only)
+    pub fn void_outright(&mut self, outright_id: U256) -> Result<bool, Error> {
+        self.only_owner_or_oracle()?;
+        self._settle_outright(outright_id, &[])
+    }
+
+    /// Pay the position of `player` in a settled or voided outright
+    pub fn claim_outright(&mut self, outright_id: U256, player: Address) -> Result<U256, Error> {
+        let outright = self.outrights.get(outright_id);
+        let status = outright.status.get().to::<u8>();
+        if status != 2 && status != 3 {
+            return Err(Error::OutrightWrongStatus(OutrightWrongStatus { outright_id, status }));
+        }
+
+        let position = outright.positions.get(player);
+        let staked = position.total.get();
+        if staked == U256::ZERO || position.paid.get() {
+            return Err(Error::NothingToClaim(NothingToClaim {
+                market_id: outright_id,
+                player,
+            }));
+        }
+
+        let (payout, commission) = if status == 3 {
+            (staked, U256::ZERO)
+        } else {
+            let commission = outright.commission.get();
+            let distributable = outright.total.get() - commission;
+            let funded_winners = outright.funded_winners.get().to::<u8>();
+            let mut payout = U256::ZERO;
+            let mut commission_share = U256::ZERO;
+            for i in 0..outright.winners.len() {
+                let winner = outright.winners.get(i).unwrap_or_default().to::<u8>();
+                let stake = position.stakes.get(Uint::<8, 1>::from(winner));
+                let outcome_pool = outright.pools.get(winner as usize).unwrap_or_default();
+                payout += StakeEngine::_dead_heat_payout(
+                    stake,
+                    outcome_pool,
+                    distributable,
+                    funded_winners,
+                );
+                commission_share +=
+                    StakeEngine::_dead_heat_payout(stake, outcome_pool, commission, funded_winners);
+            }
+            (payout, commission_share)
+        };
+        let outcome = if status == 3 {
+            OUTCOME_REFUNDED
+        } else if payout > U256::ZERO {
+            OUTCOME_WON
+        } else {
+            OUTCOME_LOST
+        };
+        let collateral = outright.collateral.get();
+
+        self.outrights
+            .setter(outright_id)
+            .positions
+            .setter(player)
+            .paid
+            .set(true);
+
+        let mut pool = self.collateral_pools.setter(collateral);
+        let total_paid = pool.total_paid.get();
+        pool.total_paid.set(total_paid + payout);
+        self._record_result(player, staked, outcome, payout, commission);
+
+        if payout > U256::ZERO {
+            self._transfer_collateral(collateral, player, payout)?;
+        }
+
+        Ok(payout)
+    }
+
+    /// Retrieve an outright as (collateral, close time, status, outcome count, total staked, winners)
+    pub fn get_outright(&self, outright_id: U256) -> (Address, u64, u8, u8, U256, Vec<u8>) {
+        let outright = self.outrights.get(outright_id);
+        let winners = (0..outright.winners.len())
+            .map(|i| outright.winners.get(i).unwrap_or_default().to::<u8>())
+            .collect();
+        (
+            outright.collateral.get(),
+            outright.close_time.get().to::<u64>(),
+            outright.status.get().to::<u8>(),
+            outright.pools.len() as u8,
+            outright.total.get(),
+            winners,
+        )
+    }
+
+    /// Retrieve a page of outcomes of an outright as (outcome, label, pool)
+    ///
+    /// # Arguments
+    /// * `offset` - The first outcome to return.
+    /// * `limit` - The maximum number of outcomes to return.
+    pub fn get_outright_outcomes(&self, outright_id: U256, offset: u8, limit: u8) -> Vec<(u8, String, U256)> {
+        let outright = self.outrights.get(outright_id);
+        let count = outright.pools.len();
+        let start = (offset as usize).min(count);
+        let end = (start + limit as usize).min(count);
+
+        (start..end)
+            .map(|i| {
+                (
+                    i as u8,
+                    outright
+                        .labels
+                        .get(i)
+                        .map(|label| label.get_string())
+                        .unwrap_or_default(),
+                    outright.pools.get(i).unwrap_or_default(),
+                )
+            })
+            .collect()
+    }
+
+    /// Amount staked by a player on an outcome of an outright
+    pub fn outright_position(&self, outright_id: U256, player: Address, outcome: u8) -> U256 {
+        let outright = self.outrights.get(outright_id);
+        let position = outright.positions.get(player);
+        position.stakes.get(Uint::<8, 1>::from(outcome))
+    }
+
+    /// Retrieve event details from the core contract
+    pub fn read_event_core(&mut self, _event_id: String) -> Result<(u64, u8, u8), Error> {
+        self._read_event_core(&_event_id)
+    }
+
+    /// Stake tokens for an event
+    ///
+    /// The stake is taken in the event's collateral. For ATON events, attached ETH is
+    /// minted into ATON; otherwise `_amount` is pulled with `transferFrom`.
+    #[payable]
+    pub fn stake(&mut self, _event_id: String, _amount: U256, _team: u8) -> Result<bool, Error> {
+        self.stake_with_limits(_event_id, _amount, _team, U256::ZERO, 0)
+    }
+
+    /// Stake tokens for an event with slippage protection
+    ///
+    /// Reverts with `SlippageExceeded` if the payout multiplier after the stake (decimal odds
+    /// scaled by `ODDS_PRECISION`) is below `_min_payout_multiplier`, and with
+    /// `DeadlineExpired` if the transaction lands after `_deadline`. Zero disables either bound.
+    #[payable]
+    pub fn stake_with_limits(
+        &mut self,
+        _event_id: String,
+        _amount: U256,
+        _team: u8,
+        _min_payout_multiplier: U256,
+        _deadline: u64,
+    ) -> Result<bool, Error> {
+        self._stake_for(
+            msg::sender(),
+            string_to_bytes8(&_event_id),
+            msg::value(),
+            _amount,
+            _team,
+            _min_payout_multiplier,
+            _deadline,
+        )
+    }
+
+    /// Bind the caller to the referrer who brought them in (once only)
+    pub fn bind_referrer(&mut self, referrer: Address) -> Result<bool, Error> {
+        let player = msg::sender();
+        let current = self.referrers.get(player);
+        if current != Address::ZERO {
+            return Err(Error::ReferrerAlreadySet(ReferrerAlreadySet { referrer: current }));
+        }
+        if referrer == Address::ZERO || referrer == player {
+            return Err(Error::InvalidReferrer(InvalidReferrer { referrer }));
+        }
+
+        self.referrers.setter(player).set(referrer);
+        let mut stats = self.referrer_stats.setter(referrer);
+        let referrals = stats.referrals.get();
+        stats.referrals.set(referrals + U256::from(1));
+
+        evm::log(ReferrerBound { player, referrer });
+
+        Ok(true)
+    }
+
+    /// Referrer bound to a player (zero when none)
+    pub fn referrer_of(&self, player: Address) -> Address {
+        self.referrers.get(player)
+    }
+
+    /// Set the share of a referred player's commission paid to the referrer (owner only)
+    ///
+    /// `share` is expressed in `PCT_DENOM` units and applies to ATON events.
+    pub fn set_referral_share(&mut self, share: U256) -> Result<bool, Error> {
+        self.only_owner()?;
+        if share > U256::from(PCT_DENOM) {
+            return Err(Error::InvalidShare(InvalidShare { share }));
+        }
+
+        self.referral_share.set(share);
+        evm::log(ReferralShareUpdated { share });
+
+        Ok(true)
+    }
+
+    /// Share of a referred player's commission paid to the referrer, in `PCT_DENOM` units
+    pub fn referral_share(&self) -> U256 {
+        self.referral_share.get()
+    }
+
+    /// Statistics of a referrer as (referrals, volume, earned, claimed, pending)
+    pub fn referrer_stats(&self, referrer: Address) -> (U256, U256, U256, U256, U256) {
+        let stats = self.referrer_stats.get(referrer);
+        (
+            stats.referrals.get(),
+            stats.volume.get(),
+            stats.earned.get(),
+            stats.claimed.get(),
+            stats.pending.get(),
+        )
+    }
+
+    /// Referral rewards a referrer accrued on an event and not released yet
+    pub fn event_referral_rewards(&self, event_id: String, referrer: Address) -> U256 {
+        self.events
+            .get(string_to_bytes8(&event_id))
+            .referral_rewards
+            .get(referrer)
+    }
+
+    /// Release the referral rewards accrued on a settled event (callable by anyone)
+    ///
+    /// Rewards accrue when referred players stake, whether they later win or lose, and
+    /// become claimable once the event settles with a commission; they are dropped when the
+    /// event is voided or its commission waived.
+    pub fn release_referral_rewards(&mut self, event_id: String, referrer: Address) -> Result<U256, Error> {
+        self._release_referral_rewards(string_to_bytes8(&event_id), referrer)
+    }
+
+    /// Transfer the caller's unclaimed referral rewards in ATON
+    pub fn claim_referral_rewards(&mut self) -> Result<U256, Error> {
+        let referrer = msg::sender();
+        let mut stats = self.referrer_stats.setter(referrer);
+        let claimed = stats.claimed.get();
+        let amount = stats.earned.get() - claimed;
+        if amount == U256::ZERO {
+            return Ok(U256::ZERO);
+        }
+        stats.claimed.set(claimed + amount);
+
+        let aton = self.aton_address.get();
+        let mut pool = self.collateral_pools.setter(aton);
+        let total_paid = pool.total_paid.get();
+        pool.total_paid.set(total_paid + amount);
+        self._transfer_collateral(aton, referrer, amount)?;
+
+        evm::log(ReferralRewardClaimed { referrer, amount });
+
+        Ok(amount)
+    }
+
+    /// Allow or revoke an account issuing promo credits (owner only)
+    pub fn set_promoter(&mut self, account: Address, allowed: bool) -> Result<bool, Error> {
+        self.only_owner()?;
+        self.promoters.setter(account).set(allowed);
+        evm::log(PromoterUpdated { account, allowed });
+        Ok(true)
+    }
+
+    /// Whether an account can issue promo credits
+    pub fn is_promoter(&self, account: Address) -> bool {
+        self.promoters.get(account)
+    }
+
+    /// Set the maximum outstanding promo credit (owner only)
+    pub fn set_promo_credit_cap(&mut self, cap: U256) -> Result<bool, Error> {
+        self.only_owner()?;
+        self.promo_credit_cap.set(cap);
+        self._log_promo_reserve();
+        Ok(true)
+    }
+
+    /// Add ATON backing promo credits; attached ETH is minted into ATON
+    #[payable]
+    pub fn fund_promo_reserve(&mut self, amount: U256) -> Result<bool, Error> {
+        let aton = self.aton_address.get();
+        let amount = self._collect_collateral(aton, msg::sender(), msg::value(), amount)?;
+        let reserve = self.promo_reserve.get();
+        self.promo_reserve.set(reserve + amount);
+        self._log_promo_reserve();
+        Ok(true)
+    }
+
+    /// Withdraw promo reserve not backing outstanding credit (owner only)
+    pub fn withdraw_promo_reserve(&mut self, amount: U256, to: Address) -> Result<bool, Error> {
+        self.only_owner()?;
+        let reserve = self.promo_reserve.get();
+        let available = reserve.saturating_sub(self.promo_outstanding.get());
+        if amount > available {
+            return Err(Error::InsufficientPromoReserve(InsufficientPromoReserve {
+                available,
+                needed: amount,
+            }));
+        }
+        self.promo_reserve.set(reserve - amount);
+
+        self._transfer_collateral(self.aton_address.get(), to, amount)?;
+        self._log_promo_reserve();
+        Ok(true)
+    }
+
+    /// Issue free-bet credit to a player, stakeable until `expires_at` (promoters only)
+    ///
+    /// Outstanding credit is capped by `promo_credit_cap` and must be backed by the promo reserve.
+    pub fn issue_credit(&mut self, player: Address, amount: U256, expires_at: u64) -> Result<bool, Error> {
+        let account = msg::sender();
+        if !self.promoters.get(account) {
+            return Err(Error::UnauthorizedAccount(UnauthorizedAccount { account }));
+        }
+        self._issue_credit(player, amount, expires_at)
+    }
+
+    /// Release the outstanding credit of a player's expired grants
+    pub fn expire_credits(&mut self, player: Address) -> U256 {
+        self._take_credit(player, U256::ZERO).unwrap_or_default()
+    }
+
+    /// Unexpired promo credit of a player
+    pub fn credit_balance(&self, player: Address) -> U256 {
+        self.get_credits(player)
+            .iter()
+            .fold(U256::ZERO, |balance, (amount, _)| balance + amount)
+    }
+
+    /// Unexpired promo credit grants of a player as (amount, expiry)
+    pub fn get_credits(&self, player: Address) -> Vec<(U256, u64)> {
+        let timestamp = block::timestamp();
+        let credits = self.promo_credits.get(player);
+        (0..credits.len())
+            .filter_map(|i| credits.get(i))
+            .map(|credit| (credit.amount.get(), credit.expires_at.get().to::<u64>()))
+            .filter(|(amount, expires_at)| *amount != U256::ZERO && *expires_at > timestamp)
+            .collect()
+    }
+
+    /// Promo credit accounting as (reserve, outstanding credit, cap)
+    pub fn promo_summary(&self) -> (U256, U256, U256) {
+        (
+            self.promo_reserve.get(),
+            self.promo_outstanding.get(),
+            self.promo_credit_cap.get(),
+        )
+    }
+
+    /// Stake promo credit on an ATON event; only net winnings are paid out
+    ///
+    /// Credit is spent soonest-expiring first. The credited part of the stake is returned to
+    /// the promo reserve at settlement instead of to the player, including on a void.
+    pub fn stake_with_credit(&mut self, _event_id: String, _amount: U256, _team: u8) -> Result<bool, Error> {
+        self._stake_credit(msg::sender(), string_to_bytes8(&_event_id), _amount, _team)
+    }
+
+    /// Allow or revoke an account seeding event pools with house liquidity (owner only)
+    pub fn set_liquidity_provider(&mut self, account: Address, allowed: bool) -> Result<bool, Error> {
+        self.only_owner()?;
+        self.liquidity_providers.setter(account).set(allowed);
+        evm::log(LiquidityProviderUpdated { account, allowed });
+        Ok(true)
+    }
+
+    /// Whether an account can seed event pools
+    pub fn is_liquidity_provider(&self, account: Address) -> bool {
+        self.liquidity_providers.get(account)
+    }
+
+    /// Seed both sides of an open ATON event with house liquidity (liquidity providers only)
+    ///
+    /// The seed prices the event like any stake, so the commission is no longer waived, but it
+    /// is not a player position: it is kept out of the player counters and is taken back with
+    /// `withdraw_liquidity` once the event is settled. The provider's self-exclusion and spend
+    /// limits apply, and so do the event's pool caps; the per-player minimum and maximum stake
+    /// do not, since the seed backs both sides rather than a single pick.
+    pub fn seed_event(&mut self, event_id: String, home_amount: U256, away_amount: U256) -> Result<bool, Error> {
+        let provider = msg::sender();
+        if !self.liquidity_providers.get(provider) {
+            return Err(Error::UnauthorizedAccount(UnauthorizedAccount { account: provider }));
+        }
+        let event_id_bytes = string_to_bytes8(&event_id);
+        self._can_stake_event(event_id_bytes)?;
+        let aton = self.aton_address.get();
+        let collateral = self._event_collateral(event_id_bytes);
+        if collateral != aton {
+            return Err(Error::LiquidityNotAccepted(LiquidityNotAccepted { collateral }));
+        }
+
+        self._collect_collateral(aton, provider, U256::ZERO, home_amount + away_amount)?;
+        self._seed_event(provider, event_id_bytes, home_amount, away_amount)
+    }
+
+    /// Return a provider's share of a settled event: the parimutuel payout of its seed on the
+    /// winning side, or the whole seed when the event is voided
+    pub fn withdraw_liquidity(&mut self, event_id: String) -> Result<U256, Error> {
+        let provider = msg::sender();
+        let event_id_bytes = string_to_bytes8(&event_id);
+        let amount = self._withdraw_liquidity(provider, event_id_bytes)?;
+        if amount > U256::ZERO {
+            self._transfer_collateral(self.aton_address.get(), provider, amount)?;
+        }
+        Ok(amount)
+    }
+
+    /// Liquidity of a provider in an event as (home seed, away seed, withdrawn)
+    pub fn get_liquidity(&self, event_id: String, provider: Address) -> (U256, U256, bool) {
+        let event = self.events.get(string_to_bytes8(&event_id));
+        let seed = event.liquidity.get(provider);
+        (
+            seed.amounts.get(0).unwrap_or_default(),
+            seed.amounts.get(1).unwrap_or_default(),
+            seed.withdrawn.get(),
+        )
+    }
+
+    /// House liquidity of an event as (home seed, away seed)
+    pub fn event_liquidity(&self, event_id: String) -> (U256, U256) {
+        let event = self.events.get(string_to_bytes8(&event_id));
+        (
+            event.seeded.get(0).unwrap_or_default(),
+            event.seeded.get(1).unwrap_or_default(),
+        )
+    }
+
+    /// House liquidity of a provider as (seeded, returned), and the ATON still deployed in events
+    pub fn liquidity_summary(&self, provider: Address) -> (U256, U256, U256) {
+        let stats = self.liquidity_stats.get(provider);
+        (
+            stats.seeded.get(),
+            stats.returned.get(),
+            self.liquidity_deployed.get(),
+        )
+    }
+
+    /// Add ATON to the bonus pool of an open event; attached ETH is minted into ATON
+    ///
+    /// Winners share the bonus pro-rata to their stake. It is refunded to the sponsors when the
+    /// event is voided or no player backed the winner.
+    #[payable]
+    pub fn sponsor_bonus(&mut self, event_id: String, amount: U256) -> Result<bool, Error> {
+        let event_id_bytes = string_to_bytes8(&event_id);
+        let status = self.events.get(event_id_bytes).status.get().to::<u8>();
+        if status != 1 {
+            return Err(Error::WrongStatus(WrongStatus {
+                event_id: event_id_bytes,
+                status,
+                expected: 1,
+            }));
+        }
+        let aton = self.aton_address.get();
+        let amount = self._collect_collateral(aton, msg::sender(), msg::value(), amount)?;
+        self._add_bonus(msg::sender(), event_id_bytes, amount);
+        Ok(true)
+    }
+
+    /// Take back the caller's bonus deposit on a settled event without winners to pay
+    pub fn refund_bonus(&mut self, event_id: String) -> Result<U256, Error> {
+        let sponsor = msg::sender();
+        let amount = self._refund_bonus(sponsor, string_to_bytes8(&event_id))?;
+        self._transfer_collateral(self.aton_address.get(), sponsor, amount)?;
+        Ok(amount)
+    }
+
+    /// Sponsored bonus pool of an event
+    pub fn event_bonus(&self, event_id: String) -> U256 {
+        self.events.get(string_to_bytes8(&event_id)).bonus.get()
+    }
+
+    /// Bonus deposited by a sponsor on an event
+    pub fn sponsored_bonus(&self, event_id: String, sponsor: Address) -> U256 {
+        self.events
+            .get(string_to_bytes8(&event_id))
+            .sponsors
+            .get(sponsor)
+    }
+
+    /// Stake on behalf of the signer of an EIP-712 `StakeOrder`, submitted by a relayer
+    ///
+    /// The stake is credited to `player`, who must have signed the order and approved the
+    /// collateral. Each nonce can be used once; a zero deadline never expires.
+    pub fn stake_by_sig(&mut self, order: StakeOrder, signature: Bytes) -> Result<bool, Error> {
+        let digest = StakeEngine::_order_digest(self.domain_separator(), &order);
+        let signer = self._recover_signer(digest, &signature)?;
+        let (event_id, team, amount, nonce, deadline, player) = order;
+        if player == Address::ZERO || signer != player {
+            return Err(Error::InvalidSignature(InvalidSignature {}));
+        }
+
+        self._use_nonce(player, nonce)?;
+        self._stake_for(
+            player,
+            string_to_bytes8(&event_id),
+            U256::ZERO,
+            amount,
+            team,
+            U256::ZERO,
+            deadline,
+        )?;
+
+        evm::log(StakeOrderFilled {
+            player,
+            relayer: msg::sender(),
+            nonce,
+        });
+
+        Ok(true)
+    }
+
+    /// Cancel a signed stake order of the caller before a relayer fills it
+    pub fn cancel_nonce(&mut self, nonce: U256) -> Result<bool, Error> {
+        let player = msg::sender();
+        self._use_nonce(player, nonce)?;
+
+        evm::log(NonceCanceled { player, nonce });
+
+        Ok(true)
+    }
+
+    /// Whether a stake order nonce of `player` was filled or canceled
+    pub fn nonce_used(&self, player: Address, nonce: U256) -> bool {
+        self.used_nonces.get(player).get(nonce)
+    }
+
+    /// EIP-712 domain separator of signed stake orders
+    pub fn domain_separator(&self) -> B256 {
+        StakeEngine::_domain_separator(block::chainid(), contract::address())
+    }
+
+    /// Retrieve stakes for an event with pagination, newest first
+    ///
+    /// # Arguments
+    /// * `offset` - The number of stakes to skip, counted from the newest one.
+    /// * `limit` - The maximum number of stakes to return.
+    ///
+    /// # Returns
+    /// * The stakes as (player, amount, team, timestamp), the total number of stakes and the
+    ///   offset of the next page (zero once the last page is reached).
+    pub fn get_stakes(&self, _event_id: String, offset: u64, limit: u64) -> Result<StakesPage, Error> {
+        let id8 = string_to_bytes8(&_event_id);
+        let stakes = self.stakes.getter(id8);
+        let total = stakes.len() as u64;
+        let (start_index, end_index, next_offset) = StakeEngine::_newest_first_page(total, offset, limit);
+
+        let mut result = Vec::with_capacity((end_index - start_index) as usize);
+        for i in (start_index..end_index).rev() {
+            if let Some(stake_guard) = stakes.get(i as usize) {
+                result.push((
+                    stake_guard.player.get(),
+                    stake_guard.amount.get(),
+                    stake_guard.team.get().to::<u8>(),
+                    stake_guard.timestamp.get().to::<u64>(),
+                ));
+            }
+        }
+
+        Ok((result, total, next_offset))
+    }
+
+    /// Quote a stake of `_amount` on `_team` against the current pools
+    ///
+    /// Returns (projected payout if `_team` wins, implied decimal odds after the stake
+    /// scaled by `ODDS_PRECISION`, commission the event would retain after the stake).
+    pub fn quote(
+        &self,
+        _event_id: String,
+        _team: u8,
+        _amount: U256,
+    ) -> Result<(U256, U256, U256), Error> {
+        self._quote(string_to_bytes8(&_event_id), _team, _amount)
+    }
+
+    /// Implied decimal odds of every outcome as (team, pool, odds scaled by `ODDS_PRECISION`)
+    pub fn odds(&self, _event_id: String) -> Vec<(u8, U256, U256)> {
+        let event = self.events.get(string_to_bytes8(&_event_id));
+        let pools = [
+            event.total.get(0).unwrap_or_default(),
+            event.total.get(1).unwrap_or_default(),
+        ];
+        let (_, total_staked, commission) = StakeEngine::_commission_for_pools(pools[0], pools[1]);
+
+        (1u8..=2)
+            .map(|team| {
+                let pool = pools[(team - 1) as usize];
+                (team, pool, StakeEngine::_implied_odds(pool, total_staked, commission))
+            })
+            .collect()
+    }
+
+    /// Pay rewards for a completed event
+    ///
+    /// When the position is represented by a bet slip, the reward goes to the slip holder.
+    pub fn pay_event(&mut self, event_id: String, player_address: Address) -> Result<bool, Error> {
+        self._pay_event(event_id, player_address)
+    }
+
+    /// Set the caller's spend limit on `token` for a period (`PERIOD_DAILY`, `PERIOD_WEEKLY` or `PERIOD_MONTHLY`)
+    ///
+    /// Lowering a limit applies immediately; raising or removing it (zero) only applies after
+    /// `LIMIT_COOLING_OFF`. Returns when the new limit takes effect.
+    pub fn set_spend_limit(&mut self, token: Address, period: u8, limit: U256) -> Result<u64, Error> {
+        self._set_spend_limit(msg::sender(), token, period, limit)
+    }
+
+    /// Exclude the caller from staking for `duration` seconds
+    ///
+    /// An exclusion can be extended but never shortened. Claims are not affected.
+    pub fn self_exclude(&mut self, duration: u64) -> Result<u64, Error> {
+        let player = msg::sender();
+        let mut controls = self.player_controls.setter(player);
+        let until = block::timestamp()
+            .saturating_add(duration)
+            .max(controls.excluded_until.get().to::<u64>());
+        controls.excluded_until.set(Uint::<64, 1>::from(until));
+
+        evm::log(SelfExclusionSet { player, until });
+
+        Ok(until)
+    }
+
+    /// Timestamp until which a player is excluded from staking (zero when never excluded)
+    pub fn excluded_until(&self, player: Address) -> u64 {
+        self.player_controls.get(player).excluded_until.get().to::<u64>()
+    }
+
+    /// Spend limit of a player as (limit in force, spent in the current window, pending limit, when it applies)
+    pub fn spend_limit(&self, player: Address, token: Address, period: u8) -> Result<(U256, U256, U256, u64), Error> {
+        let length = StakeEngine::_period_length(period)?;
+        let controls = self.player_controls.get(player);
+        let limits = controls.limits.get(token);
+        let spend_limit = match period {
+            PERIOD_DAILY => &limits.daily,
+            PERIOD_WEEKLY => &limits.weekly,
+            _ => &limits.monthly,
+        };
+
+        let timestamp = block::timestamp();
+        let pending_at = spend_limit.pending_at.get().to::<u64>();
+        let (limit, pending_limit, pending_at) = if pending_at != 0 && timestamp >= pending_at {
+            (spend_limit.pending_limit.get(), U256::ZERO, 0)
+        } else {
+            (spend_limit.limit.get(), spend_limit.pending_limit.get(), pending_at)
+        };
+        let spent = if spend_limit.window.get().to::<u64>() == timestamp / length {
+            spend_limit.spent.get()
+        } else {
+            U256::ZERO
+        };
+
+        Ok((limit, spent, pending_limit, pending_at))
+    }
+
+    /// Retrieve the settled positions of a player, oldest first
+    ///
+    /// # Arguments
+    /// * `player` - The player whose history is returned.
+    /// * `offset` - The number of entries to skip.
+    /// * `limit` - The maximum number of entries to return.
+    pub fn get_player_history(&self, player: Address, offset: u64, limit: u64) -> Vec<HistoryEntryView> {
+        let history = self.player_history.get(player);
+        let start = (offset as usize).min(history.len());
+        let end = start.saturating_add(limit as usize).min(history.len());
+
+        (start..end)
+            .filter_map(|i| history.get(i))
+            .map(|entry| {
+                (
+                    bytes8_to_string(entry.event_id.get()),
+                    entry.team.get().to::<u8>(),
+                    entry.stake.get(),
+                    entry.outcome.get().to::<u8>(),
+                    entry.payout.get(),
+                    entry.commission.get(),
+                    entry.settled_at.get().to::<u64>(),
+                )
+            })
+            .collect()
+    }
+
+    /// Number of settled positions in the history of a player
+    pub fn player_history_count(&self, player: Address) -> u64 {
+        self.player_history.get(player).len() as u64
+    }
+
+    /// Aggregated win/loss record and net profit and loss of a player
+    ///
+    /// Covers event stakes, parlays, score markets and outrights. Market and outright
+    /// positions count once claimed, losing ones included; the history only lists event stakes.
+    pub fn get_player_record(&self, player: Address) -> PlayerRecordView {
+        let record = self.player_records.get(player);
+        let total_staked = record.total_staked.get();
+        let total_payout = record.total_payout.get();
+        (
+            record.wins.get().to::<u64>(),
+            record.losses.get().to::<u64>(),
+            record.refunds.get().to::<u64>(),
+            total_staked,
+            total_payout,
+            record.total_commission.get(),
+            I256::from_raw(total_payout).wrapping_sub(I256::from_raw(total_staked)),
+        )
+    }
+
+    /// Season in progress as (season, start timestamp)
+    pub fn current_season(&self) -> (U256, u64) {
+        let season = self.season.get();
+        (season, self.seasons.get(season).started_at.get().to::<u64>())
+    }
+
+    /// Start and end timestamps of a season (the end is zero while it is in progress)
+    pub fn get_season(&self, season: U256) -> (u64, u64) {
+        let record = self.seasons.get(season);
+        (
+            record.started_at.get().to::<u64>(),
+            record.ended_at.get().to::<u64>(),
+        )
+    }
+
+    /// Results of a player in a season
+    pub fn season_stats(&self, season: U256, player: Address) -> SeasonStatsView {
+        let seasons = self.seasons.get(season);
+        let stats = seasons.stats.get(player);
+        let volume = stats.volume.get();
+        let profit = StakeEngine::_net_profit(stats.payout.get(), volume);
+        (
+            volume,
+            profit,
+            StakeEngine::_roi(profit, volume),
+            stats.wins.get().to::<u64>(),
+            stats.losses.get().to::<u64>(),
+            stats.streak.get().to::<u32>(),
+            stats.best_streak.get().to::<u32>(),
+        )
+    }
+
+    /// Standings of a season, best net profit first
+    pub fn get_leaderboard(&self, season: U256) -> Vec<LeaderboardEntry> {
+        let seasons = self.seasons.get(season);
+        (0..seasons.leaderboard.len())
+            .filter_map(|i| seasons.leaderboard.get(i))
+            .map(|player| {
+                let stats = seasons.stats.get(player);
+                let volume = stats.volume.get();
+                (player, StakeEngine::_net_profit(stats.payout.get(), volume), volume)
+            })
+            .collect()
+    }
+
+    /// Close the season in progress and start the next one (owner only)
+    pub fn roll_season(&mut self) -> Result<U256, Error> {
+        self.only_owner()?;
+        let timestamp = Uint::<64, 1>::from(block::timestamp());
+        let season = self.season.get();
+        self.seasons.setter(season).ended_at.set(timestamp);
+
+        let next = season + U256::from(1);
+        self.season.set(next);
+        self.seasons.setter(next).started_at.set(timestamp);
+
+        evm::log(SeasonRolled {
+            season: next,
+            started_at: block::timestamp(),
+        });
+
+        Ok(next)
+    }
+
+    /// Retrieve events the caller has participated in, newest first
+    ///
+    /// # Arguments
+    /// * `offset` - The number of events to skip, counted from the newest one.
+    /// * `limit` - The maximum number of events to return.
+    ///
+    /// # Returns
+    /// * The events as (event id, start date, status, pool 1, pool 2, winner), the total number
+    ///   of events and the offset of the next page (zero once the last page is reached).
+    pub fn get_player_events(&self, offset: u64, limit: u64) -> Result<PlayerEventsPage, Error> {
+        let events = self.player_events.getter(msg::sender());
+        let total = events.len() as u64;
+        let (start_index, end_index, next_offset) = StakeEngine::_newest_first_page(total, offset, limit);
+
+        let mut event_list = Vec::new();
+
+        for i in (start_index..end_index).rev() {
+            if let Some(event_id_bytes) = events.get(i as usize) {
+                let event = self.events.get(event_id_bytes);
+                event_list.push((
+                    bytes8_to_string(event_id_bytes),
+                    event.start_date.get().try_into().unwrap_or_default(),
+                    event.status.get().try_into().unwrap_or_default(),
+                    event.total.get(0).unwrap_or_default(),
+                    event.total.get(1).unwrap_or_default(),
+                    event.winner.get().try_into().unwrap_or_default(),
+                ));
+            }
+        }
+
+        Ok((event_list, total, next_offset))
+    }
+}
+
+impl StakeEngine {
+    /// Projected (payout, decimal odds, commission) of a stake of `amount` on `team`
+    fn _quote(
+        &self,
+        event_id_bytes: FixedBytes<8>,
+        team: u8,
+        amount: U256,
+    ) -> Result<(U256, U256, U256), Error> {
+        if team != 1 && team != 2 {
+            return Err(Error::InvalidTeam(InvalidTeam { team }));
+        }
+
+        let event = self.events.get(event_id_bytes);
+        let mut pools = [
+            event.total.get(0).unwrap_or_default(),
+            event.total.get(1).unwrap_or_default(),
+        ];
+        let side = (team - 1) as usize;
+        pools[side] += amount;
+
+        let (_, total_staked, commission) = StakeEngine::_commission_for_pools(pools[0], pools[1]);
+
+        Ok((
+            StakeEngine::_parimutuel_payout(amount, pools[side], total_staked, commission),
+            StakeEngine::_implied_odds(pools[side], total_staked, commission),
+            commission,
+        ))
+    }
+
+    /// Open a score market on an event still accepting stakes
+    pub(crate) fn _create_market(&mut self, event_id_bytes: FixedBytes<8>, kind: u8, line: i64) -> Result<U256, Error> {
+        self._can_stake_event(event_id_bytes)?;
+
+        let collateral = self._event_collateral(event_id_bytes);
+        let market_id = self.market_count.get() + U256::from(1);
+        self.market_count.set(market_id);
+
+        let mut market = self.markets.setter(market_id);
+        market.event_id.set(event_id_bytes);
+        market.collateral.set(collateral);
+        market.kind.set(Uint::<8, 1>::from(kind));
+        market.line.set(Signed::<64, 1>::unchecked_from(line));
+        market.status.set(Uint::<8, 1>::from(1u8));
+
+        evm::log(MarketCreated {
+            market_id,
+            event_id: event_id_bytes,
+            kind,
+            line,
+        });
+
+        Ok(market_id)
+    }
+
+    /// Settle a score market from final scores (`None` voids it) and retain its commission
+    pub(crate) fn _resolve_market(&mut self, market_id: U256, scores: Option<(u32, u32)>) {
+        let mut market = self.markets.setter(market_id);
+        let kind = market.kind.get().to::<u8>();
+        let line = market.line.get().as_i64();
+        let total = market.total.get();
+
+        let winning_outcome = scores
+            .and_then(|(home, away)| StakeEngine::_market_outcome(kind, line, home, away));
+        let winning_pool = winning_outcome
+            .map(|outcome| market.pools.get(Uint::<32, 1>::from(outcome)))
+            .unwrap_or_default();
+
+        let status = match winning_outcome {
+            Some(outcome) if winning_pool != U256::ZERO => {
+                // Nothing is retained when every stake backed the winning outcome.
+                let commission = if winning_pool == total {
+                    U256::ZERO
+                } else {
+                    total * U256::from(COMMISSION_PREMIUM) / U256::from(PCT_DENOM)
+                };
+                market.winning_outcome.set(Uint::<32, 1>::from(outcome));
+                market.commission.set(commission);
+                2u8
+            }
+            _ => 3u8,
+        };
+        market.status.set(Uint::<8, 1>::from(status));
+        let commission = market.commission.get();
+        let collateral = market.collateral.get();
+
+        let mut pool = self.collateral_pools.setter(collateral);
+        let pool_commission = pool.commission.get();
+        pool.commission.set(pool_commission + commission);
+
+        evm::log(MarketResolved {
+            market_id,
+            status,
+            winning_outcome: winning_outcome.unwrap_or_default(),
+        });
+    }
+
+    /// Winning outcome of a market for the final scores, `None` on a push
+    pub fn _market_outcome(kind: u8, line: i64, home_score: u32, away_score: u32) -> Option<u32> {
+        let home = home_score as i64 * LINE_SCALE;
+        let away = away_score as i64 * LINE_SCALE;
+        let (left, right) = match kind {
+            MARKET_TOTALS => (home + away, line),
+            MARKET_SPREAD => (home + line, away),
+            MARKET_EXACT_SCORE => {
+                if home_score > u16::MAX as u32 || away_score > u16::MAX as u32 {
+                    return None;
+                }
+                return Some((home_score << 16) | away_score);
+            }
+            _ => return None,
+        };
+
+        match left.cmp(&right) {
+            std::cmp::Ordering::Greater => Some(1),
+            std::cmp::Ordering::Less => Some(2),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    /// Index range `[start, end)` of a newest-first page over `total` items, with the next offset
+    ///
+    /// Mirrors the page/page_size slicing of CoreEvents: `offset` counts from the newest item.
+    pub fn _newest_first_page(total: u64, offset: u64, limit: u64) -> (u64, u64, u64) {
+        let end_index = total.saturating_sub(offset);
+        let start_index = end_index.saturating_sub(limit);
+        let next_offset = if start_index > 0 && limit > 0 {
+            offset + (end_index - start_index)
+        } else {
+            0
+        };
+        (start_index, end_index, next_offset)
+    }
+
+    fn _log_promo_reserve(&self) {
+        evm::log(PromoReserveUpdated {
+            reserve: self.promo_reserve.get(),
+            outstanding: self.promo_outstanding.get(),
+            cap: self.promo_credit_cap.get(),
+        });
+    }
+
+    /// Grant promo credit to `player` within the cap and the reserve
+    pub(crate) fn _issue_credit(&mut self, player: Address, amount: U256, expires_at: u64) -> Result<bool, Error> {
+        let timestamp = block::timestamp();
+        if expires_at <= timestamp {
+            return Err(Error::DeadlineExpired(DeadlineExpired {
+                deadline: expires_at,
+                timestamp,
+            }));
+        }
+
+        let outstanding = self.promo_outstanding.get() + amount;
+        let cap = self.promo_credit_cap.get();
+        if outstanding > cap {
+            return Err(Error::CreditCapExceeded(CreditCapExceeded { outstanding, cap }));
+        }
+        let reserve = self.promo_reserve.get();
+        if outstanding > reserve {
+            return Err(Error::InsufficientPromoReserve(InsufficientPromoReserve {
+                available: reserve,
+                needed: outstanding,
+            }));
+        }
+        self.promo_outstanding.set(outstanding);
+
+        let mut credits = self.promo_credits.setter(player);
+        let mut credit = credits.grow();
+        credit.amount.set(amount);
+        credit.expires_at.set(Uint::<64, 1>::from(expires_at));
+
+        evm::log(CreditIssued {
+            player,
+            amount,
+            expires_at,
+        });
+
+        Ok(true)
+    }
+
+    /// Spend `amount` of a player's credit, soonest-expiring first, pruning spent and expired grants
+    ///
+    /// Returns the expired credit released from the outstanding total.
+    pub(crate) fn _take_credit(&mut self, player: Address, amount: U256) -> Result<U256, Error> {
+        let timestamp = block::timestamp();
+        let mut credits = self.promo_credits.setter(player);
+        let mut grants: Vec<(U256, u64)> = (0..credits.len())
+            .filter_map(|i| credits.get(i))
+            .map(|credit| (credit.amount.get(), credit.expires_at.get().to::<u64>()))
+            .collect();
+
+        let expired = grants
+            .iter()
+            .filter(|(_, expires_at)| *expires_at <= timestamp)
+            .fold(U256::ZERO, |total, (credit, _)| total + credit);
+        grants.retain(|(credit, expires_at)| *credit != U256::ZERO && *expires_at > timestamp);
+
+        let available = grants.iter().fold(U256::ZERO, |total, (credit, _)| total + credit);
+        if amount > available {
+            return Err(Error::InsufficientCredit(InsufficientCredit { available, amount }));
+        }
+
+        grants.sort_by_key(|(_, expires_at)| *expires_at);
+        let mut remaining = amount;
+        for (credit, _) in grants.iter_mut() {
+            let spent = remaining.min(*credit);
+            *credit -= spent;
+            remaining -= spent;
+        }
+        grants.retain(|(credit, _)| *credit != U256::ZERO);
+
+        credits.truncate(0);
+        for (credit, expires_at) in grants {
+            let mut grant = credits.grow();
+            grant.amount.set(credit);
+            grant.expires_at.set(Uint::<64, 1>::from(expires_at));
+        }
+
+        let outstanding = self.promo_outstanding.get();
+        self.promo_outstanding.set(outstanding.saturating_sub(amount + expired));
+
+        Ok(expired)
+    }
+
+    /// Stake promo credit of `player`, moving its backing from the promo reserve into the event
+    pub(crate) fn _stake_credit(
+        &mut self,
+        player: Address,
+        event_id_bytes: FixedBytes<8>,
+        amount: U256,
+        team: u8,
+    ) -> Result<bool, Error> {
+        let collateral = self._event_collateral(event_id_bytes);
+        if collateral != self.aton_address.get() {
+            return Err(Error::CreditNotAccepted(CreditNotAccepted { collateral }));
+        }
+        self._check_stake_limits(event_id_bytes, team, amount, U256::ZERO, 0)?;
+        self._can_stake_event(event_id_bytes)?;
+
+        self._take_credit(player, amount)?;
+        let reserve = self.promo_reserve.get();
+        self.promo_reserve.set(reserve - amount);
+        self._charge_player_controls(player, collateral, amount)?;
+        self._add_stake_for(player, event_id_bytes, amount, team)?;
+
+        let mut event = self.events.setter(event_id_bytes);
+        let mut position = event.player.setter(player);
+        let credit_stake = position.credit_stake.get().to::<U256>() + amount;
+        if credit_stake > U256::from(Uint::<96, 2>::MAX) {
+            return Err(Error::StakeOverflow(StakeOverflow { stake: credit_stake }));
+        }
+        position.credit_stake.set(credit_stake.to::<Uint<96, 2>>());
+
+        let mut pool = self.collateral_pools.setter(collateral);
+        let total_staked = pool.total_staked.get();
+        pool.total_staked.set(total_staked + amount);
+
+        evm::log(CreditStaked {
+            player,
+            event_id: event_id_bytes,
+            amount,
+        });
+
+        Ok(true)
+    }
+
+    /// Split a payout into (paid to the player, returned to the promo reserve)
+    pub fn _net_of_credit(payout: U256, credit_stake: U256) -> (U256, U256) {
+        let withheld = payout.min(credit_stake);
+        (payout - withheld, withheld)
+    }
+
+    /// Add collected house liquidity of `provider` to both pools of an event
+    pub(crate) fn _seed_event(
+        &mut self,
+        provider: Address,
+        event_id_bytes: FixedBytes<8>,
+        home_amount: U256,
+        away_amount: U256,
+    ) -> Result<bool, Error> {
+        self._check_seed_limits(event_id_bytes, home_amount, away_amount)?;
+        self._charge_player_controls(provider, self.aton_address.get(), home_amount + away_amount)?;
+
+        let amounts = [home_amount, away_amount];
+        let mut event = self.events.setter(event_id_bytes);
+        for (side, amount) in amounts.into_iter().enumerate() {
+            if let Some(mut pool) = event.total.setter(side) {
+                let total = pool.get();
+                pool.set(total + amount);
+            }
+            if let Some(mut seeded) = event.seeded.setter(side) {
+                let total = seeded.get();
+                seeded.set(total + amount);
+            }
+            let mut seed = event.liquidity.setter(provider);
+            if let Some(mut seed) = seed.amounts.setter(side) {
+                let total = seed.get();
+                seed.set(total + amount);
+            }
+        }
+
+        let seeded = home_amount + away_amount;
+        let mut stats = self.liquidity_stats.setter(provider);
+        let total_seeded = stats.seeded.get();
+        stats.seeded.set(total_seeded + seeded);
+        let deployed = self.liquidity_deployed.get();
+        self.liquidity_deployed.set(deployed + seeded);
+
+        evm::log(LiquiditySeeded {
+            event_id: event_id_bytes,
+            provider,
+            home_amount,
+            away_amount,
+        });
+
+        Ok(true)
+    }
+
+    /// Settle the liquidity of `provider` in a closed event, returning the amount owed
+    pub(crate) fn _withdraw_liquidity(&mut self, provider: Address, event_id_bytes: FixedBytes<8>) -> Result<U256, Error> {
+        let event = self.events.get(event_id_bytes);
+        let status = event.status.get().to::<u8>();
+        if status != 2 {
+            return Err(Error::WrongStatus(WrongStatus {
+                event_id: event_id_bytes,
+                status,
+                expected: 2,
+            }));
+        }
+        let seed = event.liquidity.get(provider);
+        let amounts = [
+            seed.amounts.get(0).unwrap_or_default(),
+            seed.amounts.get(1).unwrap_or_default(),
+        ];
+        let seeded = amounts[0] + amounts[1];
+        if seed.withdrawn.get() || seeded == U256::ZERO {
+            return Err(Error::NoLiquidity(NoLiquidity {
+                event_id: event_id_bytes,
+                provider,
+            }));
+        }
+        let winner = event.winner.get().to::<u8>();
+        let pools = [
+            event.total.get(0).unwrap_or_default(),
+            event.total.get(1).unwrap_or_default(),
+        ];
+
+        let (waive_commission, _, commission) = StakeEngine::_commission_for_pools(pools[0], pools[1]);
+        let amount = StakeEngine::_liquidity_return(amounts, pools, winner, commission);
+        let voided = winner == 0 || winner == 3;
+        self._accrue_event_commission(event_id_bytes, waive_commission || voided, commission);
+
+        self.events
+            .setter(event_id_bytes)
+            .liquidity
+            .setter(provider)
+            .withdrawn
+            .set(true);
+        let mut stats = self.liquidity_stats.setter(provider);
+        let returned = stats.returned.get();
+        stats.returned.set(returned + amount);
+        let deployed = self.liquidity_deployed.get();
+        self.liquidity_deployed.set(deployed.saturating_sub(seeded));
+
+        evm::log(LiquidityWithdrawn {
+            event_id: event_id_bytes,
+            provider,
+            amount,
+        });
+
+        Ok(amount)
+    }
+
+    /// Amount owed on a seed of both sides: the whole seed on a tie or cancellation (winner 0
+    /// or 3), otherwise the parimutuel payout of the seed on the winning side
+    pub fn _liquidity_return(seed: [U256; 2], pools: [U256; 2], winner: u8, commission: U256) -> U256 {
+        match winner {
+            1 | 2 => {
+                let side = (winner - 1) as usize;
+                StakeEngine::_parimutuel_payout(seed[side], pools[side], pools[0] + pools[1], commission)
+            }
+            _ => seed[0] + seed[1],
+        }
+    }
+
+    /// Record a collected bonus deposit of `sponsor`
+    pub(crate) fn _add_bonus(&mut self, sponsor: Address, event_id_bytes: FixedBytes<8>, amount: U256) {
+        let mut event = self.events.setter(event_id_bytes);
+        let bonus = event.bonus.get();
+        event.bonus.set(bonus + amount);
+        let mut deposit = event.sponsors.setter(sponsor);
+        let deposited = deposit.get();
+        deposit.set(deposited + amount);
+
+        evm::log(BonusSponsored {
+            event_id: event_id_bytes,
+            sponsor,
+            amount,
+        });
+    }
+
+    /// Clear the bonus deposit of `sponsor` on a settled event without winners, returning it
+    pub(crate) fn _refund_bonus(&mut self, sponsor: Address, event_id_bytes: FixedBytes<8>) -> Result<U256, Error> {
+        let event = self.events.get(event_id_bytes);
+        let status = event.status.get().to::<u8>();
+        if status != 2 {
+            return Err(Error::WrongStatus(WrongStatus {
+                event_id: event_id_bytes,
+                status,
+                expected: 2,
+            }));
+        }
+        let winner = event.winner.get().to::<u8>();
+        if winner == 1 || winner == 2 {
+            let side = (winner - 1) as usize;
+            let player_pool = event.total.get(side).unwrap_or_default()
+                - event.seeded.get(side).unwrap_or_default();
+            if player_pool != U256::ZERO {
+                return Err(Error::BonusNotRefundable(BonusNotRefundable {
+                    event_id: event_id_bytes,
+                }));
+            }
+        }
+
+        let amount = event.sponsors.get(sponsor);
+        if amount == U256::ZERO {
+            return Err(Error::NoBonus(NoBonus {
+                event_id: event_id_bytes,
+                sponsor,
+            }));
+        }
+        let mut event = self.events.setter(event_id_bytes);
+        event.sponsors.setter(sponsor).set(U256::ZERO);
+        let bonus = event.bonus.get();
+        event.bonus.set(bonus.saturating_sub(amount));
+
+        evm::log(BonusRefunded {
+            event_id: event_id_bytes,
+            sponsor,
+            amount,
+        });
+
+        Ok(amount)
+    }
+
+    /// Share of the bonus pool owed to a winning `stake`, pro-rata to the player stakes of its side
+    pub fn _bonus_share(stake: U256, bonus: U256, player_pool: U256) -> U256 {
+        if player_pool == U256::ZERO {
+            return U256::ZERO;
+        }
+        stake * bonus / player_pool
+    }
+
+    /// Retain the commission of a settled event in its collateral pool, once
+    fn _accrue_event_commission(&mut self, event_id_bytes: FixedBytes<8>, waived: bool, commission: U256) {
+        let mut event = self.events.setter(event_id_bytes);
+        let commission_accrued = event.commission_accrued.get();
+        event.commission_accrued.set(true);
+        if waived || commission_accrued {
+            return;
+        }
+        let collateral = self._event_collateral(event_id_bytes);
+        let mut pool = self.collateral_pools.setter(collateral);
+        let pool_commission = pool.commission.get();
+        pool.commission.set(pool_commission + commission);
+    }
+
+    /// Count an ATON stake towards the volume of the player's referrer and accrue their reward
+    ///
+    /// Every stake pays `COMMISSION_PREMIUM / PCT_DENOM` of itself into the event commission,
+    /// so the reward is known upfront; it is held on the event until it settles.
+    pub(crate) fn _record_referral_volume(
+        &mut self,
+        player: Address,
+        event_id_bytes: FixedBytes<8>,
+        collateral: Address,
+        amount: U256,
+    ) {
+        let referrer = self.referrers.get(player);
+        if referrer == Address::ZERO || collateral != self.aton_address.get() {
+            return;
+        }
+        let reward = StakeEngine::_referral_reward(amount, self.referral_share.get());
+        let mut stats = self.referrer_stats.setter(referrer);
+        let volume = stats.volume.get();
+        stats.volume.set(volume + amount);
+        if reward == U256::ZERO {
+            return;
+        }
+        let pending = stats.pending.get();
+        stats.pending.set(pending + reward);
+
+        let mut event = self.events.setter(event_id_bytes);
+        let mut accrued = event.referral_rewards.setter(referrer);
+        let total = accrued.get();
+        accrued.set(total + reward);
+
+        evm::log(ReferralRewardAccrued {
+            referrer,
+            player,
+            event_id: event_id_bytes,
+            amount: reward,
+        });
+    }
+
+    /// Move the rewards a referrer accrued on a settled event from pending to earned
+    ///
+    /// The reward is taken out of the commission retained in the ATON pool.
+    pub(crate) fn _release_referral_rewards(
+        &mut self,
+        event_id_bytes: FixedBytes<8>,
+        referrer: Address,
+    ) -> Result<U256, Error> {
+        let status = self.events.get(event_id_bytes).status.get().to::<u8>();
+        if status != 2 {
+            return Err(Error::WrongStatus(WrongStatus {
+                event_id: event_id_bytes,
+                status,
+                expected: 2,
+            }));
+        }
+        let amount = self.events.get(event_id_bytes).referral_rewards.get(referrer);
+        if amount == U256::ZERO {
+            return Ok(U256::ZERO);
+        }
+
+        let (waive_commission, _, commission) = self._calculate_event_commission(event_id_bytes)?;
+        let winner = self.events.get(event_id_bytes).winner.get();
+        let voided = winner == Uint::<8, 1>::from(0u8) || winner == Uint::<8, 1>::from(3u8);
+        self._accrue_event_commission(event_id_bytes, waive_commission || voided, commission);
+
+        self.events
+            .setter(event_id_bytes)
+            .referral_rewards
+            .setter(referrer)
+            .set(U256::ZERO);
+        let mut stats = self.referrer_stats.setter(referrer);
+        let pending = stats.pending.get();
+        stats.pending.set(pending - amount);
+        if waive_commission || voided {
+            return Ok(U256::ZERO);
+        }
+        let earned = stats.earned.get();
+        stats.earned.set(earned + amount);
+
+        let mut pool = self.collateral_pools.setter(self.aton_address.get());
+        let pool_commission = pool.commission.get();
+        pool.commission.set(pool_commission.saturating_sub(amount));
+
+        evm::log(ReferralRewardReleased {
+            referrer,
+            event_id: event_id_bytes,
+            amount,
+        });
+
+        Ok(amount)
+    }
+
+    /// Referrer share of the commission a stake pays into its event
+    pub fn _referral_reward(stake: U256, share: U256) -> U256 {
+        stake * U256::from(COMMISSION_PREMIUM) / U256::from(PCT_DENOM) * share / U256::from(PCT_DENOM)
+    }
+
+    /// Count a settled position towards the season in progress and re-rank the player
+    pub(crate) fn _record_season(&mut self, player: Address, stake: U256, payout: U256, outcome: u8) {
+        if outcome != OUTCOME_WON && outcome != OUTCOME_LOST {
+            return;
+        }
+        let season = self.season.get();
+        let mut seasons = self.seasons.setter(season);
+        let mut stats = seasons.stats.setter(player);
+        let volume = stats.volume.get();
+        stats.volume.set(volume + stake);
+        if outcome == OUTCOME_WON {
+            let total_payout = stats.payout.get();
+            stats.payout.set(total_payout + payout);
+            let wins = stats.wins.get();
+            stats.wins.set(wins + Uint::<64, 1>::from(1u64));
+            let streak = stats.streak.get() + Uint::<32, 1>::from(1u32);
+            stats.streak.set(streak);
+            if streak > stats.best_streak.get() {
+                stats.best_streak.set(streak);
+            }
+        } else {
+            let losses = stats.losses.get();
+            stats.losses.set(losses + Uint::<64, 1>::from(1u64));
+            stats.streak.set(Uint::<32, 1>::ZERO);
+        }
+
+        self._update_leaderboard(season, player);
+    }
+
+    /// Move `player` to their rank on the leaderboard of `season`, keeping the top `LEADERBOARD_SIZE`
+    fn _update_leaderboard(&mut self, season: U256, player: Address) {
+        let mut seasons = self.seasons.setter(season);
+        let profit_of = |stats: &SeasonStats| StakeEngine::_net_profit(stats.payout.get(), stats.volume.get());
+        let mut ranked: Vec<(Address, I256)> = (0..seasons.leaderboard.len())
+            .filter_map(|i| seasons.leaderboard.get(i))
+            .filter(|ranked| *ranked != player)
+            .map(|ranked| (ranked, profit_of(&seasons.stats.get(ranked))))
+            .collect();
+
+        // Ties keep the player who got there first ahead.
+        let profit = profit_of(&seasons.stats.get(player));
+        let rank = ranked.iter().position(|(_, other)| profit > *other).unwrap_or(ranked.len());
+        ranked.insert(rank, (player, profit));
+        ranked.truncate(LEADERBOARD_SIZE);
+
+        seasons.leaderboard.truncate(0);
+        for (ranked, _) in ranked {
+            seasons.leaderboard.push(ranked);
+        }
+    }
+
+    /// Net profit of a payout on a volume
+    pub fn _net_profit(payout: U256, volume: U256) -> I256 {
+        I256::from_raw(payout).wrapping_sub(I256::from_raw(volume))
+    }
+
+    /// Return on investment in `PCT_DENOM` units (zero without volume)
+    pub fn _roi(profit: I256, volume: U256) -> I256 {
+        if volume == U256::ZERO {
+            return I256::ZERO;
+        }
+        profit * I256::from_raw(U256::from(PCT_DENOM)) / I256::from_raw(volume)
     }
 
-    /// Structure representing a player's stake in an event
-    pub struct Stake {
-        uint256 amount; // The total amount of tokens staked by the player.
-        uint8 team; // The team the player is betting on.
-        uint64 timestamp; // Timestamp of the stake.
+    /// Append a settled position to the history of a player and update their record
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn _record_history(
+        &mut self,
+        player: Address,
+        event_id_bytes: FixedBytes<8>,
+        team: u8,
+        stake: U256,
+        outcome: u8,
+        payout: U256,
+        commission: U256,
+    ) {
+        let mut history = self.player_history.setter(player);
+        let mut entry = history.grow();
+        entry.event_id.set(event_id_bytes);
+        entry.team.set(Uint::<8, 1>::from(team));
+        entry.stake.set(stake);
+        entry.outcome.set(Uint::<8, 1>::from(outcome));
+        entry.payout.set(payout);
+        entry.commission.set(commission);
+        entry.settled_at.set(Uint::<64, 1>::from(block::timestamp()));
+
+        self._record_result(player, stake, outcome, payout, commission);
     }
 
-    /// Structure representing a player in an event
-    pub struct Player {
-        uint256 stake; // The total amount of tokens staked by the player.
-        uint8 team; // The team the player is betting on.
-        bool paid; // Whether the player has been paid their reward.
+    /// Update the win/loss record of a player with a settled position of any product
+    pub(crate) fn _record_result(
+        &mut self,
+        player: Address,
+        stake: U256,
+        outcome: u8,
+        payout: U256,
+        commission: U256,
+    ) {
+        let mut record = self.player_records.setter(player);
+        match outcome {
+            OUTCOME_WON => {
+                let wins = record.wins.get();
+                record.wins.set(wins + Uint::<64, 1>::from(1u64));
+            }
+            OUTCOME_LOST => {
+                let losses = record.losses.get();
+                record.losses.set(losses + Uint::<64, 1>::from(1u64));
+            }
+            _ => {
+                let refunds = record.refunds.get();
+                record.refunds.set(refunds + Uint::<64, 1>::from(1u64));
+            }
+        }
+        let total_staked = record.total_staked.get();
+        record.total_staked.set(total_staked + stake);
+        let total_payout = record.total_payout.get();
+        record.total_payout.set(total_payout + payout);
+        let total_commission = record.total_commission.get();
+        record.total_commission.set(total_commission + commission);
     }
 
-    /// Structure representing an event for betting
-    pub struct Event {
-        uint64 start_date; // The start date and time of the event.
-        mapping(address => Player) player; // Mapping of players in the event.
-        uint256[2] total; // Total stakes for each team.
-        uint8 winner; // The winner of the event.
-        uint8 status; // Status of the event (e.g., unopen, open, closed, paid).
+    /// Length in seconds of a spend limit period
+    fn _period_length(period: u8) -> Result<u64, Error> {
+        match period {
+            PERIOD_DAILY => Ok(86_400),
+            PERIOD_WEEKLY => Ok(7 * 86_400),
+            PERIOD_MONTHLY => Ok(30 * 86_400),
+            _ => Err(Error::InvalidPeriod(InvalidPeriod { period })),
+        }
     }
-}
 
-// Implementation of the StakeEngine functionality
-#[public]
-impl StakeEngine {
-    /// Initialize the StakeEngine with the required addresses
-    pub fn initialize(
+    /// Set a spend limit of `player`, queueing raises and removals behind the cooling-off
+    pub(crate) fn _set_spend_limit(
         &mut self,
-        _aton_address: Address,
-        _core_address: Address,
-    ) -> Result<bool, Error> {
-        if self.aton_address.get() != Address::ZERO {
-            return Err(Error::ErrorCode(ErrorCode {}));
-        }
+        player: Address,
+        token: Address,
+        period: u8,
+        limit: U256,
+    ) -> Result<u64, Error> {
+        StakeEngine::_period_length(period)?;
+        let timestamp = block::timestamp();
+
+        let mut controls = self.player_controls.setter(player);
+        let mut limits = controls.limits.setter(token);
+        let spend_limit = match period {
+            PERIOD_DAILY => &mut limits.daily,
+            PERIOD_WEEKLY => &mut limits.weekly,
+            _ => &mut limits.monthly,
+        };
+        StakeEngine::_apply_pending_limit(spend_limit, timestamp);
+
+        let current = spend_limit.limit.get();
+        let tightens = limit != U256::ZERO && (current == U256::ZERO || limit <= current);
+        let effective_at = if tightens {
+            spend_limit.limit.set(limit);
+            spend_limit.pending_limit.set(U256::ZERO);
+            spend_limit.pending_at.set(Uint::<64, 1>::ZERO);
+            timestamp
+        } else {
+            let effective_at = timestamp + LIMIT_COOLING_OFF;
+            spend_limit.pending_limit.set(limit);
+            spend_limit.pending_at.set(Uint::<64, 1>::from(effective_at));
+            effective_at
+        };
+
+        evm::log(SpendLimitSet {
+            player,
+            token,
+            period,
+            limit,
+            effective_at,
+        });
 
-        self.aton_address.set(_aton_address);
+        Ok(effective_at)
+    }
 
-        let aton_contract = self._get_aton_contract();
-        let config = Call::new_in(self);
+    /// Promote a pending limit whose cooling-off has elapsed
+    fn _apply_pending_limit(spend_limit: &mut SpendLimit, timestamp: u64) {
+        let pending_at = spend_limit.pending_at.get().to::<u64>();
+        if pending_at != 0 && timestamp >= pending_at {
+            let pending_limit = spend_limit.pending_limit.get();
+            spend_limit.limit.set(pending_limit);
+            spend_limit.pending_limit.set(U256::ZERO);
+            spend_limit.pending_at.set(Uint::<64, 1>::ZERO);
+        }
+    }
 
-        let _vault_address = aton_contract
-            .vault(config)
-            .map_err(|_| Error::ErrorCode(ErrorCode {}))?;
+    /// Reject stakes from self-excluded players and count `amount` against their spend limits
+    pub(crate) fn _charge_player_controls(
+        &mut self,
+        player: Address,
+        token: Address,
+        amount: U256,
+    ) -> Result<(), Error> {
+        let timestamp = block::timestamp();
+        let mut controls = self.player_controls.setter(player);
+        let until = controls.excluded_until.get().to::<u64>();
+        if timestamp < until {
+            return Err(Error::SelfExcluded(SelfExcluded { player, until }));
+        }
 
-        self.vault_address.set(_vault_address);
-        self.core_address.set(_core_address);
+        let mut limits = controls.limits.setter(token);
+        for period in [PERIOD_DAILY, PERIOD_WEEKLY, PERIOD_MONTHLY] {
+            let window = timestamp / StakeEngine::_period_length(period)?;
+            let spend_limit = match period {
+                PERIOD_DAILY => &mut limits.daily,
+                PERIOD_WEEKLY => &mut limits.weekly,
+                _ => &mut limits.monthly,
+            };
+            StakeEngine::_apply_pending_limit(spend_limit, timestamp);
 
-        Ok(true)
-    }
+            let mut spent = amount;
+            if spend_limit.window.get().to::<u64>() == window {
+                spent += spend_limit.spent.get();
+            }
+            let limit = spend_limit.limit.get();
+            if limit != U256::ZERO && spent > limit {
+                return Err(Error::SpendLimitExceeded(SpendLimitExceeded { period, spent, limit }));
+            }
+            spend_limit.window.set(Uint::<64, 1>::from(window));
+            spend_limit.spent.set(spent);
+        }
 
-    /// Retrieve event details from the core contract
-    pub fn read_event_core(&mut self, _event_id: String) -> Result<(u64, u8, u8), Error> {
-        self._read_event_core(&_event_id)
+        Ok(())
     }
 
-    /// Stake tokens for an event
-    #[payable]
-    pub fn stake(&mut self, _event_id: String, _amount: U256, _team: u8) -> Result<bool, Error> {
-        let _value = msg::value();
-        let event_id_bytes = string_to_bytes8(&_event_id);
+    /// Record a stake on an outcome of an outright
+    pub(crate) fn _add_outright_stake(&mut self, outright_id: U256, player: Address, outcome: u8, amount: U256) {
+        let mut outright = self.outrights.setter(outright_id);
+        let collateral = outright.collateral.get();
+        let total = outright.total.get();
+        outright.total.set(total + amount);
+        if let Some(mut pool) = outright.pools.setter(outcome as usize) {
+            let pool_total = pool.get();
+            pool.set(pool_total + amount);
+        }
+        let mut position = outright.positions.setter(player);
+        let mut stake = position.stakes.setter(Uint::<8, 1>::from(outcome));
+        let staked = stake.get();
+        stake.set(staked + amount);
+        let position_total = position.total.get();
+        position.total.set(position_total + amount);
+
+        let mut collateral_pool = self.collateral_pools.setter(collateral);
+        let total_staked = collateral_pool.total_staked.get();
+        collateral_pool.total_staked.set(total_staked + amount);
+
+        evm::log(OutrightStake {
+            outright_id,
+            player,
+            outcome,
+            amount,
+        });
+    }
 
-        self._can_stake_event(event_id_bytes.clone())?;
+    /// Settle an open outright with its winners, voiding it when no winner was backed
+    pub(crate) fn _settle_outright(&mut self, outright_id: U256, winners: &[u8]) -> Result<bool, Error> {
+        let mut outright = self.outrights.setter(outright_id);
+        let status = outright.status.get().to::<u8>();
+        if status != 1 {
+            return Err(Error::OutrightWrongStatus(OutrightWrongStatus { outright_id, status }));
+        }
+        // A result known while stakes are accepted could be backed after the fact.
+        let close_time = outright.close_time.get().to::<u64>();
+        if block::timestamp() < close_time {
+            return Err(Error::OutrightNotClosed(OutrightNotClosed {
+                outright_id,
+                close_time,
+            }));
+        }
 
-        let aton_contract = self._get_aton_contract();
-        let mut _final_amount = U256::from(0);
+        let count = outright.pools.len();
+        let mut winning_pools = U256::ZERO;
+        let mut funded_winners = 0u8;
+        for (i, &winner) in winners.iter().enumerate() {
+            if winner as usize >= count || winners[..i].contains(&winner) {
+                return Err(Error::InvalidOutcome(InvalidOutcome {
+                    outcome: winner as u32,
+                }));
+            }
+            let pool = outright.pools.get(winner as usize).unwrap_or_default();
+            if pool != U256::ZERO {
+                winning_pools += pool;
+                funded_winners += 1;
+            }
+        }
 
-        if _value > U256::from(0) {
-            let config = Call::new_in(self).value(_value);
-            aton_contract.mint_aton(config).map_err(|_| Error::ErrorCode(ErrorCode {}))?;
-            _final_amount = _value;
-        } else {
-            let config = Call::new_in(self);
-            aton_contract.transfer_from(config, msg::sender(), contract::address(), _amount)
-                .map_err(|_| Error::ErrorCode(ErrorCode {}))?;
-            _final_amount = _amount;
+        let total = outright.total.get();
+        let status = if funded_winners == 0 { 3u8 } else { 2u8 };
+        if status == 2 {
+            // Nothing is retained when every stake backed a winner.
+            let commission = if winning_pools == total {
+                U256::ZERO
+            } else {
+                total * U256::from(COMMISSION_PREMIUM) / U256::from(PCT_DENOM)
+            };
+            outright.commission.set(commission);
+            outright.funded_winners.set(Uint::<8, 1>::from(funded_winners));
+            for &winner in winners {
+                outright.winners.push(Uint::<8, 1>::from(winner));
+            }
         }
+        outright.status.set(Uint::<8, 1>::from(status));
+        let commission = outright.commission.get();
+        let collateral = outright.collateral.get();
+
+        let mut pool = self.collateral_pools.setter(collateral);
+        let pool_commission = pool.commission.get();
+        pool.commission.set(pool_commission + commission);
+
+        evm::log(OutrightSettled {
+            outright_id,
+            status,
+            winners: winners.to_vec(),
+        });
 
-        self._add_stake(event_id_bytes, _final_amount, _team)?;
         Ok(true)
     }
 
-    /// Retrieve stakes for an event with pagination
-    pub fn get_stakes(
-        &self,
-        _event_id: String,
-        page_size: u64,
-    ) -> Result<Vec<(U256, u8, u64)>, Error> {
-        let id8 = string_to_bytes8(&_event_id);
-        let stakes = self.stakes.getter(id8);
-        let event = self.events.getter(id8);
+    /// Payout of a stake on a winning outcome when `funded_winners` backed outcomes dead-heat
+    pub fn _dead_heat_payout(
+        stake: U256,
+        outcome_pool: U256,
+        distributable: U256,
+        funded_winners: u8,
+    ) -> U256 {
+        if outcome_pool == U256::ZERO || funded_winners == 0 {
+            return U256::ZERO;
+        }
+        stake * distributable / (outcome_pool * U256::from(funded_winners))
+    }
+
+    /// Current decimal odds of a parlay leg, scaled by `ODDS_PRECISION`
+    pub(crate) fn _leg_odds(&self, event_id_bytes: FixedBytes<8>, team: u8) -> Result<U256, Error> {
+        if team != 1 && team != 2 {
+            return Err(Error::InvalidTeam(InvalidTeam { team }));
+        }
 
-        if event.status.get() == Uint::<8, 1>::from(0u8) {
-            return Ok(Vec::new());
+        let event = self.events.get(event_id_bytes);
+        let pools = [
+            event.total.get(0).unwrap_or_default(),
+            event.total.get(1).unwrap_or_default(),
+        ];
+        let (_, total_staked, commission) = StakeEngine::_commission_for_pools(pools[0], pools[1]);
+        let odds = StakeEngine::_implied_odds(pools[(team - 1) as usize], total_staked, commission);
+        if odds == U256::ZERO {
+            return Err(Error::LegNotPriced(LegNotPriced {
+                event_id: event_id_bytes,
+                team,
+            }));
         }
 
-        let end_index = std::cmp::min(page_size as usize, stakes.len());
+        Ok(odds)
+    }
 
-        let mut result: Vec<(U256, u8, u64)> = Vec::with_capacity(end_index);
+    /// Payout of a parlay stake: `amount` times the product of the leg odds
+    pub fn _parlay_payout(amount: U256, odds: &[U256]) -> U256 {
+        odds.iter()
+            .fold(amount, |payout, leg_odds| payout * leg_odds / U256::from(ODDS_PRECISION))
+    }
 
-        for i in 0..end_index {
-            if let Some(stake_guard) = stakes.get(i) {
-                result.push((
-                    stake_guard.amount.get(),
-                    stake_guard.team.get().try_into().unwrap(),
-                    stake_guard.timestamp.get().try_into().unwrap(),
-                ));
+    /// Enforce the player's deadline and minimum payout multiplier on a stake
+    pub(crate) fn _check_stake_limits(
+        &self,
+        event_id_bytes: FixedBytes<8>,
+        team: u8,
+        amount: U256,
+        min_payout_multiplier: U256,
+        deadline: u64,
+    ) -> Result<(), Error> {
+        // Reject transactions landing after the player's deadline.
+        let timestamp = block::timestamp();
+        if deadline != 0 && timestamp > deadline {
+            return Err(Error::DeadlineExpired(DeadlineExpired {
+                deadline,
+                timestamp,
+            }));
+        }
+
+        // Reject the stake if the pool moved against the player past their tolerance.
+        if min_payout_multiplier > U256::ZERO {
+            let (_, multiplier, _) = self._quote(event_id_bytes, team, amount)?;
+            if multiplier < min_payout_multiplier {
+                return Err(Error::SlippageExceeded(SlippageExceeded {
+                    multiplier,
+                    min_multiplier: min_payout_multiplier,
+                }));
             }
         }
 
-        Ok(result)
+        Ok(())
     }
 
-    /// Pay rewards for a completed event
-    pub fn pay_event(&mut self, event_id: String, player_address: Address) -> Result<bool, Error> {
-        self._pay_event(event_id, player_address)
+    /// Effective limits of an event as (min stake, max player stake, max outcome pool, max imbalance)
+    fn _stake_limits(&self, event_id_bytes: FixedBytes<8>) -> (U256, U256, U256, U256) {
+        let event = self.events.get(event_id_bytes);
+        let limits = if event.limits.configured.get() {
+            &event.limits
+        } else {
+            &self.default_limits
+        };
+
+        (
+            limits.min_stake.get(),
+            limits.max_player_stake.get(),
+            limits.max_outcome_pool.get(),
+            limits.max_imbalance.get(),
+        )
+    }
+
+    fn _set_limits(
+        limits: &mut StakeLimits,
+        min_stake: U256,
+        max_player_stake: U256,
+        max_outcome_pool: U256,
+        max_imbalance: U256,
+    ) {
+        limits.configured.set(true);
+        limits.min_stake.set(min_stake);
+        limits.max_player_stake.set(max_player_stake);
+        limits.max_outcome_pool.set(max_outcome_pool);
+        limits.max_imbalance.set(max_imbalance);
     }
 
-    /// Retrieve events a player has participated in
-    pub fn get_player_events(
+    /// Enforce the event stake limits on a stake of `amount` on `team` by a player
+    /// already holding `player_stake` in the event
+    pub(crate) fn _check_pool_limits(
         &self,
-        page_size: u64,
-    ) -> Result<Vec<(String, u64, u8, U256, U256, u8)>, Error> {
-        let events = self.player_events.getter(msg::sender());
-        let end_index = std::cmp::min(page_size, events.len() as u64);
+        event_id_bytes: FixedBytes<8>,
+        player_stake: U256,
+        team: u8,
+        amount: U256,
+    ) -> Result<(), Error> {
+        let (min_stake, max_player_stake, max_outcome_pool, max_imbalance) =
+            self._stake_limits(event_id_bytes);
 
-        let mut event_list = Vec::new();
+        if amount < min_stake {
+            return Err(Error::StakeBelowMinimum(StakeBelowMinimum { amount, min_stake }));
+        }
 
-        for i in 0..end_index {
-            if let Some(event_id_bytes) = events.get(i as usize) {
-                let event = self.events.get(event_id_bytes);
-                event_list.push((
-                    bytes8_to_string(event_id_bytes),
-                    event.start_date.get().try_into().unwrap_or_default(),
-                    event.status.get().try_into().unwrap_or_default(),
-                    event.total.get(0).unwrap_or_default(),
-                    event.total.get(1).unwrap_or_default(),
-                    event.winner.get().try_into().unwrap_or_default(),
-                ));
+        let stake = player_stake + amount;
+        if max_player_stake != U256::ZERO && stake > max_player_stake {
+            return Err(Error::PlayerStakeExceeded(PlayerStakeExceeded {
+                stake,
+                max_player_stake,
+            }));
+        }
+
+        let event = self.events.get(event_id_bytes);
+        let side = (team - 1) as usize;
+        let pool = event.total.get(side).unwrap_or_default() + amount;
+        let other_pool = event.total.get(1 - side).unwrap_or_default();
+
+        if max_outcome_pool != U256::ZERO && pool > max_outcome_pool {
+            return Err(Error::OutcomePoolExceeded(OutcomePoolExceeded {
+                pool,
+                max_outcome_pool,
+            }));
+        }
+
+        // Only stakes widening the gap between the pools are limited.
+        let imbalance = pool.saturating_sub(other_pool);
+        if max_imbalance != U256::ZERO && imbalance > max_imbalance {
+            return Err(Error::PoolImbalanceExceeded(PoolImbalanceExceeded {
+                imbalance,
+                max_imbalance,
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Enforce the event pool caps on a seed of `home_amount` and `away_amount`
+    pub(crate) fn _check_seed_limits(
+        &self,
+        event_id_bytes: FixedBytes<8>,
+        home_amount: U256,
+        away_amount: U256,
+    ) -> Result<(), Error> {
+        let (_, _, max_outcome_pool, max_imbalance) = self._stake_limits(event_id_bytes);
+        let event = self.events.get(event_id_bytes);
+        let before = [
+            event.total.get(0).unwrap_or_default(),
+            event.total.get(1).unwrap_or_default(),
+        ];
+        let after = [before[0] + home_amount, before[1] + away_amount];
+
+        for pool in after {
+            if max_outcome_pool != U256::ZERO && pool > max_outcome_pool {
+                return Err(Error::OutcomePoolExceeded(OutcomePoolExceeded {
+                    pool,
+                    max_outcome_pool,
+                }));
             }
         }
 
-        Ok(event_list)
+        // Only seeds widening the gap between the pools are limited.
+        let gap = |pools: [U256; 2]| pools[0].max(pools[1]) - pools[0].min(pools[1]);
+        let imbalance = gap(after);
+        if max_imbalance != U256::ZERO && imbalance > max_imbalance && imbalance > gap(before) {
+            return Err(Error::PoolImbalanceExceeded(PoolImbalanceExceeded {
+                imbalance,
+                max_imbalance,
+            }));
+        }
+
+        Ok(())
     }
-}
 
-impl StakeEngine {
     fn _read_event_core(&mut self, _event_id: &str) -> Result<(u64, u8, u8), Error> {
         // Create an instance of the ATON contract interface
         let core_contract = ICoreEvents::new(self.core_address.get());
@@ -221,14 +3290,20 @@ impl StakeEngine {
         // Retrieve the event details from the ATON contract
         let (start_date, status, winner) = core_contract
             .get_event(config, _event_id.to_string())
-            .map_err(|_| Error::ErrorCode(ErrorCode {}))?;
+            .map_err(|_| {
+                Error::EventNotFound(EventNotFound {
+                    event_id: string_to_bytes8(_event_id),
+                })
+            })?;
         let event_id_bytes = string_to_bytes8(_event_id);
-        let mut event = self.events.setter(event_id_bytes);
 
-        if event.status.get() == Uint::<8, 1>::from(0u8) {
-            return Err(Error::ErrorCode(ErrorCode {}));
+        // CoreEvents reports unknown events with a zero start date.
+        if start_date == 0 {
+            return Err(Error::EventNotFound(EventNotFound { event_id: event_id_bytes }));
         }
 
+        let mut event = self.events.setter(event_id_bytes);
+
        
         event.status.set(Uint::<8, 1>::from(status)); // Set the event status
         event.winner.set(Uint::<8, 1>::from(winner));
@@ -244,65 +3319,236 @@ impl StakeEngine {
                 .unwrap_or_default(),
         ))
     }
+    /// Returns (waive_commission, total_staked, commission) for an event.
+    /// The commission is waived (and zero) while one side of the pool is empty.
     fn _calculate_event_commission(
         &mut self,
         event_id_bytes: FixedBytes<8>,
     ) -> Result<(bool, U256, U256), Error> {
-        let premium = U256::from(200000);
-        let pct_denom = U256::from(10000000);
+        let e = self.events.get(event_id_bytes);
+
+        Ok(StakeEngine::_commission_for_pools(
+            e.total.get(0).unwrap_or_default(),
+            e.total.get(1).unwrap_or_default(),
+        ))
+    }
 
-        let e = self.events.setter(event_id_bytes);
+    /// Commission applied to a two-sided pool: `COMMISSION_PREMIUM / PCT_DENOM` of the total,
+    /// waived when either side is empty.
+    pub fn _commission_for_pools(pool_1: U256, pool_2: U256) -> (bool, U256, U256) {
+        let total_staked = pool_1 + pool_2;
+        let waive_commission = pool_1 == U256::ZERO || pool_2 == U256::ZERO;
+        let commission = if waive_commission {
+            U256::ZERO
+        } else {
+            total_staked * U256::from(COMMISSION_PREMIUM) / U256::from(PCT_DENOM)
+        };
+
+        (waive_commission, total_staked, commission)
+    }
 
-        let total_staked = e.total.get(0).unwrap() + e.total.get(1).unwrap();
-        let commission = total_staked * premium / pct_denom;
-        let mut waive_commission = true;
+    /// Parimutuel payout of `stake` on the winning side: its share of the winning pool
+    /// applied to the whole pool net of commission.
+    pub fn _parimutuel_payout(
+        stake: U256,
+        winning_pool: U256,
+        total_staked: U256,
+        commission: U256,
+    ) -> U256 {
+        if winning_pool == U256::ZERO {
+            return U256::ZERO;
+        }
+        stake * (total_staked - commission) / winning_pool
+    }
+
+    /// Implied decimal odds of a side, scaled by `ODDS_PRECISION` (zero for an empty side).
+    pub fn _implied_odds(side_pool: U256, total_staked: U256, commission: U256) -> U256 {
+        if side_pool == U256::ZERO {
+            return U256::ZERO;
+        }
+        (total_staked - commission) * U256::from(ODDS_PRECISION) / side_pool
+    }
 
-        if e.total.get(0).unwrap() == U256::ZERO || e.total.get(1).unwrap() == U256::ZERO {
-            waive_commission = false;
+    /// Mark a stake order nonce of `player` as used
+    fn _use_nonce(&mut self, player: Address, nonce: U256) -> Result<(), Error> {
+        let mut nonces = self.used_nonces.setter(player);
+        if nonces.get(nonce) {
+            return Err(Error::NonceUsed(NonceUsed { player, nonce }));
         }
+        nonces.setter(nonce).set(true);
+        Ok(())
+    }
 
+    /// EIP-712 domain separator for a chain and verifying contract
+    pub fn _domain_separator(chain_id: u64, verifying_contract: Address) -> B256 {
+        let mut encoded = Vec::with_capacity(5 * 32);
+        encoded.extend_from_slice(crypto::keccak(EIP712_DOMAIN_TYPE).as_slice());
+        encoded.extend_from_slice(crypto::keccak(EIP712_NAME).as_slice());
+        encoded.extend_from_slice(crypto::keccak(EIP712_VERSION).as_slice());
+        encoded.extend_from_slice(&U256::from(chain_id).to_be_bytes::<32>());
+        encoded.extend_from_slice(verifying_contract.into_word().as_slice());
+        crypto::keccak(encoded)
+    }
 
-        return Ok((waive_commission, total_staked, commission));
+    /// EIP-712 digest of a stake order, as signed by the player
+    pub fn _order_digest(domain_separator: B256, order: &StakeOrder) -> B256 {
+        let (event_id, team, amount, nonce, deadline, player) = order;
+        let mut encoded = Vec::with_capacity(7 * 32);
+        encoded.extend_from_slice(crypto::keccak(STAKE_ORDER_TYPE).as_slice());
+        encoded.extend_from_slice(crypto::keccak(event_id.as_bytes()).as_slice());
+        encoded.extend_from_slice(&U256::from(*team).to_be_bytes::<32>());
+        encoded.extend_from_slice(&amount.to_be_bytes::<32>());
+        encoded.extend_from_slice(&nonce.to_be_bytes::<32>());
+        encoded.extend_from_slice(&U256::from(*deadline).to_be_bytes::<32>());
+        encoded.extend_from_slice(player.into_word().as_slice());
+        let struct_hash = crypto::keccak(encoded);
+
+        let mut message = Vec::with_capacity(2 + 2 * 32);
+        message.extend_from_slice(&[0x19, 0x01]);
+        message.extend_from_slice(domain_separator.as_slice());
+        message.extend_from_slice(struct_hash.as_slice());
+        crypto::keccak(message)
     }
 
-    fn _add_stake(&mut self, event_id_key: FixedBytes<8>, amount: U256, team: u8) -> Result<bool, Error> {
-        // Ensure the team is valid
-        if team != 1 && team != 2 {
-            return Err(Error::ErrorCode(ErrorCode {}));
+    /// Recover the signer of `digest` from a 65-byte `r || s || v` signature
+    fn _recover_signer(&self, digest: B256, signature: &[u8]) -> Result<Address, Error> {
+        let (r, s, v) = StakeEngine::_split_signature(signature)
+            .ok_or(Error::InvalidSignature(InvalidSignature {}))?;
+
+        let mut input = Vec::with_capacity(4 * 32);
+        input.extend_from_slice(digest.as_slice());
+        input.extend_from_slice(&U256::from(v).to_be_bytes::<32>());
+        input.extend_from_slice(r.as_slice());
+        input.extend_from_slice(s.as_slice());
+
+        let output = RawCall::new_static()
+            .call(ECRECOVER, &input)
+            .map_err(|_| Error::InvalidSignature(InvalidSignature {}))?;
+        if output.len() != 32 {
+            return Err(Error::InvalidSignature(InvalidSignature {}));
+        }
+        Ok(Address::from_slice(&output[12..]))
+    }
+
+    /// Split a signature into (r, s, v), rejecting malformed and malleable ones
+    pub fn _split_signature(signature: &[u8]) -> Option<(B256, B256, u8)> {
+        if signature.len() != 65 {
+            return None;
+        }
+        let r = B256::from_slice(&signature[..32]);
+        let s = B256::from_slice(&signature[32..64]);
+        let v = match signature[64] {
+            0 | 1 => signature[64] + 27,
+            v => v,
+        };
+        if (v != 27 && v != 28) || U256::from_be_bytes(s.0) > SECP256K1_HALF_ORDER {
+            return None;
         }
+        Some((r, s, v))
+    }
 
+    /// Stake on behalf of `player`, who provides the collateral
+    #[allow(clippy::too_many_arguments)]
+    fn _stake_for(
+        &mut self,
+        player: Address,
+        event_id_bytes: FixedBytes<8>,
+        _value: U256,
+        _amount: U256,
+        _team: u8,
+        _min_payout_multiplier: U256,
+        _deadline: u64,
+    ) -> Result<bool, Error> {
+        let stake_amount = if _value > U256::ZERO { _value } else { _amount };
+        self._check_stake_limits(
+            event_id_bytes,
+            _team,
+            stake_amount,
+            _min_payout_multiplier,
+            _deadline,
+        )?;
 
-        // Check if the event exists
-        let event = self.events.get(event_id_key);
-        if event.status.get() != Uint::<8, 1>::from(1u8) {
-            return Err(Error::ErrorCode(ErrorCode {}));
+        self._can_stake_event(event_id_bytes.clone())?;
+
+        let collateral = self._event_collateral(event_id_bytes);
+        if !self.collateral_pools.get(collateral).allowed.get() {
+            return Err(Error::CollateralNotAllowed(CollateralNotAllowed { token: collateral }));
         }
 
-        // Ensure the event has started
-        if Uint::<64, 1>::from(block::timestamp()) < event.start_date.get() {
-            return Err(Error::ErrorCode(ErrorCode {}));
+        let _final_amount = self._collect_collateral(collateral, player, _value, _amount)?;
+        self._charge_player_controls(player, collateral, _final_amount)?;
+
+        self._add_stake_for(player, event_id_bytes, _final_amount, _team)?;
+        self._record_referral_volume(player, event_id_bytes, collateral, _final_amount);
+
+        let mut pool = self.collateral_pools.setter(collateral);
+        let total_staked = pool.total_staked.get();
+        pool.total_staked.set(total_staked + _final_amount);
+
+        Ok(true)
+    }
+
+    pub(crate) fn _add_stake(&mut self, event_id_key: FixedBytes<8>, amount: U256, team: u8) -> Result<bool, Error> {
+        self._add_stake_for(msg::sender(), event_id_key, amount, team)
+    }
+
+    /// Record a stake of `player` on `team`
+    pub(crate) fn _add_stake_for(
+        &mut self,
+        player: Address,
+        event_id_key: FixedBytes<8>,
+        amount: U256,
+        team: u8,
+    ) -> Result<bool, Error> {
+        // Ensure the team is valid
+        if team != 1 && team != 2 {
+            return Err(Error::InvalidTeam(InvalidTeam { team }));
         }
 
-        let mut event_data = self.events.setter(event_id_key);
 
-    
+        // Ensure the event is open and has not started
+        self._can_stake_event(event_id_key)?;
+        let event = self.events.get(event_id_key);
 
-        let player = msg::sender();
-        let previous_stake = event_data.player.get(player).stake.get();
-        let previous_team = event_data.player.get(player).team.get();
+        let previous_stake = event.player.get(player).stake.get().to::<U256>();
+        let previous_team = event.player.get(player).team.get();
 
         // Validate team change and staking logic
         if previous_team != Uint::<8, 1>::from(team) && previous_stake != U256::ZERO {
-            return Err(Error::ErrorCode(ErrorCode {}));
+            return Err(Error::TeamLocked(TeamLocked {
+                team: previous_team.to::<u8>(),
+            }));
         }
 
-     
+        self._check_pool_limits(event_id_key, previous_stake, team, amount)?;
+
+        let mut event_data = self.events.setter(event_id_key);
+
         let updated_stake = previous_stake + amount;
+        if updated_stake > U256::from(u128::MAX) {
+            return Err(Error::StakeOverflow(StakeOverflow {
+                stake: updated_stake,
+            }));
+        }
 
         let mut _player =event_data.player.setter(player);
 
-        _player.stake.set(updated_stake);
+        _player.stake.set(updated_stake.to::<Uint<128, 2>>());
         _player.team.set(Uint::<8, 1>::from(team));
+        let slip_id = _player.slip_id.get();
+
+        // Add the stake to the pool of its outcome
+        let side = (team - 1) as usize;
+        let mut pool = event_data.total.setter(side).unwrap();
+        let pool_total = pool.get();
+        pool.set(pool_total + amount);
+
+        // Track the event for the player on their first stake
+        if previous_stake == U256::ZERO {
+            self.player_events.setter(player).insert(event_id_key);
+        }
+
+        self._issue_slip(event_id_key, player, team, amount, slip_id)?;
 
 
        
@@ -310,6 +3556,7 @@ impl StakeEngine {
 
         // Add stake to event's stake records
         let mut new_stake = _stakes.grow();
+        new_stake.player.set(player);
         new_stake.amount.set(amount);
         new_stake.team.set(Uint::<8, 1>::from(team));
         new_stake
@@ -327,68 +3574,147 @@ impl StakeEngine {
         Ok(true)
     }
 
-    fn _can_stake_event(&self, event_id_key: FixedBytes<8>) -> Result<(), Error> {
-        let event = self.events.get(event_id_key);
-        if event.status.get() != Uint::<8, 1>::from(1u8)
-            || Uint::<64, 1>::from(block::timestamp()) >= event.start_date.get()
-        {
-            return Err(Error::ErrorCode(ErrorCode {}));
+    /// Mint a bet slip for a new position, or add `amount` to the slip of an existing one
+    fn _issue_slip(
+        &mut self,
+        event_id_key: FixedBytes<8>,
+        player: Address,
+        team: u8,
+        amount: U256,
+        slip_id: U256,
+    ) -> Result<(), Error> {
+        let bet_slip_address = self.bet_slip_address.get();
+        if bet_slip_address == Address::ZERO {
+            return Ok(());
         }
+        let bet_slip = IBetSlip::new(bet_slip_address);
+
+        if slip_id == U256::ZERO {
+            let token_id = bet_slip
+                .mint(Call::new_in(self), player, event_id_key, team, amount)
+                .map_err(|_| Error::SlipCallFailed(SlipCallFailed { token_id: slip_id }))?;
+
+            self.events
+                .setter(event_id_key)
+                .player
+                .setter(player)
+                .slip_id
+                .set(token_id);
+            let mut position = self.slip_positions.setter(token_id);
+            position.event_id.set(event_id_key);
+            position.player.set(player);
+            return Ok(());
+        }
+
+        // A sold or gifted slip cannot be topped up by the original player.
+        let holder = self._slip_holder(slip_id)?;
+        if holder != player {
+            return Err(Error::SlipTransferred(SlipTransferred {
+                token_id: slip_id,
+                holder,
+            }));
+        }
+
+        bet_slip
+            .increase(Call::new_in(self), slip_id, amount)
+            .map_err(|_| Error::SlipCallFailed(SlipCallFailed { token_id: slip_id }))?;
+
         Ok(())
     }
 
-    /// Generic function to remove an event from a `StorageVec`.
-    fn _remove_event(&mut self,
-        event_id_bytes: FixedBytes<8>,
-       player_address: Address
-    ) -> Result<(), Error> {
-    let mut events = self.player_events.setter(player_address);
-
-        // Get the length of the events vector
-        let length = events.len();
+    /// Current holder of a bet slip
+    fn _slip_holder(&mut self, slip_id: U256) -> Result<Address, Error> {
+        IBetSlip::new(self.bet_slip_address.get())
+            .owner_of(Call::new_in(self), slip_id)
+            .map_err(|_| Error::SlipCallFailed(SlipCallFailed { token_id: slip_id }))
+    }
 
-        // Find the index of the event to remove
-        let mut index_to_remove: Option<usize> = None;
+    pub(crate) fn _can_stake_event(&self, event_id_key: FixedBytes<8>) -> Result<(), Error> {
+        let event = self.events.get(event_id_key);
+        let status = event.status.get();
+        if status != Uint::<8, 1>::from(1u8) {
+            return Err(Error::WrongStatus(WrongStatus {
+                event_id: event_id_key,
+                status: status.to::<u8>(),
+                expected: 1,
+            }));
+        }
 
-        for i in 0..length {
-            if let Some(event) = events.get(i) {
-                if event == event_id_bytes {
-                    index_to_remove = Some(i);
-                    break;
-                }
-            }
+        let start_date = event.start_date.get().to::<u64>();
+        let timestamp = block::timestamp();
+        if timestamp >= start_date {
+            return Err(Error::AlreadyStarted(AlreadyStarted {
+                event_id: event_id_key,
+                start_date,
+                timestamp,
+            }));
         }
+        Ok(())
+    }
 
-        // If the event is not found, return an error
-        if index_to_remove.is_none() {
-            return Err(Error::ErrorCode(ErrorCode {}));
+    /// Remove a settled event from the open positions of a player.
+    fn _remove_event(&mut self, event_id_bytes: FixedBytes<8>, player_address: Address) -> Result<(), Error> {
+        if !self.player_events.setter(player_address).remove(event_id_bytes) {
+            return Err(Error::PlayerEventNotFound(PlayerEventNotFound {
+                event_id: event_id_bytes,
+                player: player_address,
+            }));
         }
 
-        // Swap the event to remove with the last element and pop it off
-        let index = index_to_remove.unwrap();
+        Ok(())
+    }
 
-        if index < length - 1 {
-            // Replace the element at `index` with the last element
-            if let Some(last_event) = events.get(length - 1) {
-                events.setter(index).unwrap().set(last_event);
+    /// Take `amount` of `collateral` from `from`, or mint ATON from attached ETH
+    fn _collect_collateral(
+        &mut self,
+        collateral: Address,
+        from: Address,
+        value: U256,
+        amount: U256,
+    ) -> Result<U256, Error> {
+        if value > U256::ZERO {
+            // Only ATON can be minted from ETH.
+            if collateral != self.aton_address.get() {
+                return Err(Error::EthNotAccepted(EthNotAccepted { collateral }));
             }
+            let aton_contract = self._get_aton_contract();
+            let config = Call::new_in(self).value(value);
+            aton_contract
+                .mint_aton(config)
+                .map_err(|_| Error::MintFailed(MintFailed { value }))?;
+            return Ok(value);
         }
 
-        // Remove the last element
-        events.pop();
-
-        Ok(())
+        let token = IERC20::new(collateral);
+        let config = Call::new_in(self);
+        token
+            .transfer_from(config, from, contract::address(), amount)
+            .map_err(|_| {
+                Error::TransferFailed(TransferFailed {
+                    token: collateral,
+                    from,
+                    to: contract::address(),
+                    amount,
+                })
+            })?;
+
+        Ok(amount)
     }
 
-    fn _transfer_aton(&mut self, to: Address, amount: U256) -> Result<(), Error> {
-        // Create an instance of the ATON contract interface
-
-        // Retrieve the vault address from the ATON contract
-        let _ = self._get_aton_contract()
+    fn _transfer_collateral(&mut self, token: Address, to: Address, amount: U256) -> Result<(), Error> {
+        // Create an instance of the collateral token interface
+        let _ = IERC20::new(token)
             .transfer(Call::new_in(self), to, amount)
-            .map_err(|_| Error::ErrorCode(ErrorCode {}))?;
+            .map_err(|_| {
+                Error::TransferFailed(TransferFailed {
+                    token,
+                    from: contract::address(),
+                    to,
+                    amount,
+                })
+            })?;
 
-        return Ok(());
+        Ok(())
     }
 
 
@@ -403,36 +3729,116 @@ fn _pay_event(&mut self, event_id: String, player_address: Address) -> Result<bo
     let event_winner = e.winner.get();
 
     if e.status.get() != Uint::<8, 1>::from(2u8) {
-        return Err(Error::ErrorCode(ErrorCode {}));
+        return Err(Error::WrongStatus(WrongStatus {
+            event_id: event_id_bytes,
+            status: e.status.get().to::<u8>(),
+            expected: 2,
+        }));
     }
 
+    // A tie (0) or a canceled event (3) voids the event: every stake is refunded.
+    let voided = event_winner == Uint::<8, 1>::from(0u8) || event_winner == Uint::<8, 1>::from(3u8);
+
     let player_stake = e.player.get(player_address);
-    let _stake = player_stake.stake.get();
-    let player_reward = if _stake > U256::ZERO && !e.player.get(player_address).paid.get() {
-        if event_winner == player_stake.team.get() {
-            let reward = if waive_commission {
-                _stake
-            } else {
-                (_stake * (total_staked - commission)) / total_staked
-            };
-           
-            reward
+    let _stake = player_stake.stake.get().to::<U256>();
+    let slip_id = player_stake.slip_id.get();
+    let already_paid = player_stake.paid.get();
+    let player_stake_team = player_stake.team.get().to::<u8>();
+    let credit_stake = player_stake.credit_stake.get().to::<U256>();
+    let gross_reward = if _stake > U256::ZERO && !e.player.get(player_address).paid.get() {
+        if voided {
+            _stake
+        } else if event_winner == player_stake.team.get() {
+            let winning_pool = e
+                .total
+                .get(event_winner.to::<usize>() - 1)
+                .unwrap_or_default();
+            StakeEngine::_parimutuel_payout(_stake, winning_pool, total_staked, commission)
         } else {
             U256::ZERO
         }
     } else {
         U256::ZERO
     };
+    // The credited part of the stake goes back to the promo reserve, not to the player.
+    let (player_reward, credit_returned) = StakeEngine::_net_of_credit(gross_reward, credit_stake);
+    // Winners also share the sponsored bonus, paid in ATON.
+    let bonus_reward = if !voided && gross_reward > U256::ZERO {
+        let side = event_winner.to::<usize>() - 1;
+        let player_pool = e.total.get(side).unwrap_or_default() - e.seeded.get(side).unwrap_or_default();
+        StakeEngine::_bonus_share(_stake, e.bonus.get(), player_pool)
+    } else {
+        U256::ZERO
+    };
 
         let mut _player =e.player.setter(player_address);
         _player.paid.set(true);
 
+        self._accrue_event_commission(event_id_bytes, waive_commission || voided, commission);
+        let collateral = self._event_collateral(event_id_bytes);
+        let mut pool = self.collateral_pools.setter(collateral);
+        let total_paid = pool.total_paid.get();
+        pool.total_paid.set(total_paid + player_reward);
+
+        // Positions represented by a bet slip are paid to the current holder.
+        let mut recipient = player_address;
+        if slip_id != U256::ZERO && !already_paid && self.bet_slip_address.get() != Address::ZERO {
+            recipient = self._slip_holder(slip_id)?;
+            IBetSlip::new(self.bet_slip_address.get())
+                .burn(Call::new_in(self), slip_id)
+                .map_err(|_| Error::SlipCallFailed(SlipCallFailed { token_id: slip_id }))?;
+        }
 
-
-  
          if player_reward > U256::ZERO {
-                self._transfer_aton(player_address, player_reward)?;
+                self._transfer_collateral(collateral, recipient, player_reward)?;
+            }
+        if credit_returned > U256::ZERO {
+            let reserve = self.promo_reserve.get();
+            self.promo_reserve.set(reserve + credit_returned);
+        }
+        if bonus_reward > U256::ZERO {
+            self._transfer_collateral(self.aton_address.get(), recipient, bonus_reward)?;
+            evm::log(BonusPaid {
+                event_id: event_id_bytes,
+                player: player_address,
+                amount: bonus_reward,
+            });
+        }
+
+        if _stake > U256::ZERO && !already_paid {
+            let outcome = if voided {
+                OUTCOME_REFUNDED
+            } else if gross_reward > U256::ZERO {
+                OUTCOME_WON
+            } else {
+                OUTCOME_LOST
+            };
+            // Winners bear the commission pro-rata to their share of the winning pool.
+            let player_commission = if outcome == OUTCOME_WON && !waive_commission {
+                let winning_pool = self
+                    .events
+                    .get(event_id_bytes)
+                    .total
+                    .get(event_winner.to::<usize>() - 1)
+                    .unwrap_or_default();
+                _stake * commission / winning_pool
+            } else {
+                U256::ZERO
+            };
+            self._record_history(
+                player_address,
+                event_id_bytes,
+                player_stake_team,
+                _stake,
+                outcome,
+                player_reward,
+                player_commission,
+            );
+            // Seasons rank tipsters on the parimutuel results of ATON events.
+            if collateral == self.aton_address.get() {
+                self._record_season(player_address, _stake, gross_reward, outcome);
             }
+        }
 
         self._remove_event(event_id_bytes, player_address)?;
 
@@ -445,4 +3851,45 @@ fn _pay_event(&mut self, event_id: String, player_address: Address) -> Result<bo
     fn _get_aton_contract(&self) -> IATON {
         IATON::new(self.aton_address.get())
     }
+
+    fn _set_vault_aton(&mut self, _aton_address: Address, _vault_address: Address) {
+        self.aton_address.set(_aton_address);
+        self.vault_address.set(_vault_address);
+    }
+
+    /// Collateral token of an event, defaulting to ATON
+    fn _event_collateral(&self, event_id_bytes: FixedBytes<8>) -> Address {
+        let collateral = self.events.get(event_id_bytes).collateral.get();
+        if collateral == Address::ZERO {
+            self.aton_address.get()
+        } else {
+            collateral
+        }
+    }
+
+    fn _set_collateral(&mut self, token: Address, allowed: bool) {
+        let mut pool = self.collateral_pools.setter(token);
+        pool.allowed.set(allowed);
+        if !pool.listed.get() {
+            pool.listed.set(true);
+            self.collateral_list.push(token);
+        }
+    }
+
+    fn only_owner_or_oracle(&self) -> Result<(), Error> {
+        let sender = msg::sender();
+        if self._owner.get() != sender && self.oracle_address.get() != sender {
+            return Err(Error::UnauthorizedAccount(UnauthorizedAccount { account: sender }));
+        }
+        Ok(())
+    }
+
+    fn only_owner(&self) -> Result<(), Error> {
+        if self._owner.get() != msg::sender() {
+            return Err(Error::UnauthorizedAccount(UnauthorizedAccount {
+                account: msg::sender(),
+            }));
+        }
+        Ok(())
+    }
 }
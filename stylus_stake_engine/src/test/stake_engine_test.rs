@@ -2,11 +2,18 @@
 
 #[cfg(test)]
 mod tests {
-    use crate::StakeEngine;
+    use crate::{
+        AlreadyStarted, Player, Stake, StakeOrder, LIMIT_COOLING_OFF, PERIOD_DAILY, PERIOD_MONTHLY,
+        PERIOD_WEEKLY, Error, InvalidTeam, StakeEngine, WrongStatus, MARKET_EXACT_SCORE,
+        MARKET_SPREAD, MARKET_TOTALS, MAX_OUTRIGHT_OUTCOMES, ODDS_PRECISION, LEADERBOARD_SIZE, OUTCOME_LOST,
+        OUTCOME_REFUNDED, OUTCOME_WON,
+    };
+    use std::time::{Duration, Instant};
     use stylus_sdk::{
-        alloy_primitives::{address, Address, U256},
+        alloy_primitives::{address, Address, FixedBytes, Uint, I256, U256},
         block, msg,
         prelude::*,
+        storage::{StorageFixedBytes, StorageType, StorageVec},
     };
     //     // If you are not actually using these two, comment them out:
     //     // use crate::test::constants::env_vars::{get_env_vars, EnvVars};
@@ -41,4 +48,1387 @@ mod tests {
 
 
     }
+
+    #[motsu::test]
+    fn collateral(contract: StakeEngine) {
+        let aton: Address = ATON_ADDRESS
+            .parse()
+            .expect("Should parse valid hex address");
+        let usdc = address!("7e32b54800705876d3b5cfbc7d9c226a211f7c1a");
+        let event_id = "WXHG1234".to_string();
+
+        contract._set_vault_aton(aton, Address::ZERO);
+
+        // Only the owner can manage collaterals.
+        assert!(contract.set_collateral(usdc, true).is_err());
+        contract._owner.set(msg::sender());
+        assert!(contract.set_collateral(Address::ZERO, true).is_err());
+        assert!(contract.set_collateral(usdc, true).is_ok());
+
+        // Events default to ATON until another allowed collateral is chosen.
+        assert_eq!(contract.event_collateral(event_id.clone()), aton);
+        assert!(contract.set_event_collateral(event_id.clone(), usdc).is_ok());
+        assert_eq!(contract.event_collateral(event_id.clone()), usdc);
+
+        // Disallowed tokens can't be chosen and stay listed for payouts.
+        assert!(contract.set_collateral(usdc, false).is_ok());
+        assert!(contract.set_event_collateral("ABCR3570".to_string(), usdc).is_err());
+        let collaterals = contract.get_collaterals();
+        assert_eq!(collaterals.len(), 1);
+        assert_eq!(collaterals[0].0, usdc);
+        assert!(!collaterals[0].1);
+
+        // The collateral is locked once the event holds stakes.
+        assert!(contract.set_collateral(usdc, true).is_ok());
+        let id8 = crate::tools::string_to_bytes8(&event_id);
+        contract
+            .events
+            .setter(id8)
+            .total
+            .setter(0)
+            .unwrap()
+            .set(U256::from(1));
+        assert!(contract.set_event_collateral(event_id, aton).is_err());
+    }
+
+    #[motsu::test]
+    fn quote_and_odds(contract: StakeEngine) {
+        let event_id = "WXHG1234".to_string();
+        let id8 = crate::tools::string_to_bytes8(&event_id);
+        let one = U256::from(ODDS_PRECISION);
+
+        assert!(contract.quote(event_id.clone(), 3, U256::from(1)).is_err());
+
+        // A one-sided pool waives the commission: the stake is simply returned.
+        let (payout, odds, commission) = contract
+            .quote(event_id.clone(), 1, U256::from(100))
+            .unwrap_or_default();
+        assert_eq!(payout, U256::from(100));
+        assert_eq!(odds, one);
+        assert_eq!(commission, U256::ZERO);
+
+        {
+            let mut event = contract.events.setter(id8);
+            event.total.setter(0).unwrap().set(U256::from(300));
+            event.total.setter(1).unwrap().set(U256::from(600));
+        }
+
+        // 100 on team 1: pools 400 / 600, 2% of 1000 retained.
+        let (payout, odds, commission) = contract
+            .quote(event_id.clone(), 1, U256::from(100))
+            .unwrap_or_default();
+        assert_eq!(commission, U256::from(20));
+        assert_eq!(payout, U256::from(245));
+        assert_eq!(odds, one * U256::from(245) / U256::from(100));
+
+        // Current odds: 900 - 18 = 882 over each side.
+        let odds = contract.odds(event_id);
+        assert_eq!(odds.len(), 2);
+        assert_eq!(odds[0], (1, U256::from(300), one * U256::from(882) / U256::from(300)));
+        assert_eq!(odds[1], (2, U256::from(600), one * U256::from(882) / U256::from(600)));
+    }
+
+    #[motsu::test]
+    fn stake_limits(contract: StakeEngine) {
+        let id8 = crate::tools::string_to_bytes8("WXHG1234");
+        let one = U256::from(ODDS_PRECISION);
+        let amount = U256::from(100);
+
+        {
+            let mut event = contract.events.setter(id8);
+            event.total.setter(0).unwrap().set(U256::from(300));
+            event.total.setter(1).unwrap().set(U256::from(600));
+        }
+
+        // No bounds: always accepted.
+        assert!(contract._check_stake_limits(id8, 1, amount, U256::ZERO, 0).is_ok());
+
+        // A deadline already in the past is rejected.
+        let err = contract._check_stake_limits(id8, 1, amount, U256::ZERO, 1);
+        assert!(matches!(err, Err(Error::DeadlineExpired(_))));
+
+        // Post-stake multiplier on team 1 is 2.45x.
+        let exact = one * U256::from(245) / U256::from(100);
+        assert!(contract._check_stake_limits(id8, 1, amount, exact, 0).is_ok());
+        let err = contract._check_stake_limits(id8, 1, amount, exact + U256::from(1), 0);
+        assert!(matches!(err, Err(Error::SlippageExceeded(_))));
+    }
+
+    #[motsu::test]
+    fn stake_limits_config(contract: StakeEngine) {
+        let event_id = "WXHG1234".to_string();
+        let id8 = crate::tools::string_to_bytes8(&event_id);
+        let player = msg::sender();
+        contract._owner.set(player);
+
+        // Unlimited until configured.
+        assert_eq!(contract.max_stake(event_id.clone(), player, 1), U256::MAX);
+        assert!(contract._check_pool_limits(id8, U256::ZERO, 1, U256::from(1)).is_ok());
+
+        contract
+            .set_default_stake_limits(
+                U256::from(10),
+                U256::from(500),
+                U256::from(1_000),
+                U256::from(200),
+            )
+            .unwrap_or_default();
+        assert_eq!(
+            contract.stake_limits(event_id.clone()),
+            (U256::from(10), U256::from(500), U256::from(1_000), U256::from(200))
+        );
+
+        {
+            let mut event = contract.events.setter(id8);
+            event.total.setter(0).unwrap().set(U256::from(700));
+            event.total.setter(1).unwrap().set(U256::from(600));
+        }
+
+        let err = contract._check_pool_limits(id8, U256::ZERO, 1, U256::from(9));
+        assert!(matches!(err, Err(Error::StakeBelowMinimum(_))));
+        let err = contract._check_pool_limits(id8, U256::from(450), 2, U256::from(60));
+        assert!(matches!(err, Err(Error::PlayerStakeExceeded(_))));
+        let err = contract._check_pool_limits(id8, U256::ZERO, 2, U256::from(401));
+        assert!(matches!(err, Err(Error::OutcomePoolExceeded(_))));
+        let err = contract._check_pool_limits(id8, U256::ZERO, 1, U256::from(101));
+        assert!(matches!(err, Err(Error::PoolImbalanceExceeded(_))));
+        assert!(contract._check_pool_limits(id8, U256::ZERO, 1, U256::from(100)).is_ok());
+
+        // Both sides are capped by the imbalance before the outcome pool.
+        assert_eq!(contract.max_stake(event_id.clone(), player, 1), U256::from(100));
+        assert_eq!(contract.max_stake(event_id.clone(), player, 2), U256::from(300));
+
+        // Event limits override the defaults until cleared.
+        contract
+            .set_event_stake_limits(event_id.clone(), U256::ZERO, U256::ZERO, U256::ZERO, U256::ZERO)
+            .unwrap_or_default();
+        assert_eq!(contract.max_stake(event_id.clone(), player, 1), U256::MAX);
+        contract.clear_event_stake_limits(event_id.clone()).unwrap_or_default();
+        assert_eq!(contract.max_stake(event_id, player, 1), U256::from(100));
+    }
+
+    #[motsu::test]
+    fn typed_errors(contract: StakeEngine) {
+        let event_id = "WXHG1234".to_string();
+        let id8 = crate::tools::string_to_bytes8(&event_id);
+
+        let err = contract.quote(event_id.clone(), 3, U256::from(1));
+        assert!(matches!(err, Err(Error::InvalidTeam(InvalidTeam { team: 3 }))));
+
+        let err = contract.set_collateral(Address::ZERO, true);
+        assert!(matches!(err, Err(Error::UnauthorizedAccount(_))));
+
+        // Unknown events are not open.
+        let err = contract._can_stake_event(id8);
+        assert!(matches!(
+            err,
+            Err(Error::WrongStatus(WrongStatus { status: 0, expected: 1, .. }))
+        ));
+
+        // Open events cannot be staked on once started.
+        {
+            let mut event = contract.events.setter(id8);
+            event.status.set(Uint::<8, 1>::from(1u8));
+            event.start_date.set(Uint::<64, 1>::from(1u64));
+        }
+        let err = contract._can_stake_event(id8);
+        assert!(matches!(
+            err,
+            Err(Error::AlreadyStarted(AlreadyStarted { start_date: 1, .. }))
+        ));
+
+        let err = contract.pay_event(event_id, Address::ZERO);
+        assert!(matches!(
+            err,
+            Err(Error::WrongStatus(WrongStatus { status: 1, expected: 2, .. }))
+        ));
+    }
+
+    #[motsu::test]
+    fn add_stake_records_position(contract: StakeEngine) {
+        let event_id = "WXHG1234".to_string();
+        let id8 = crate::tools::string_to_bytes8(&event_id);
+        let player = msg::sender();
+        {
+            let mut event = contract.events.setter(id8);
+            event.status.set(Uint::<8, 1>::from(1u8));
+            event.start_date.set(Uint::<64, 1>::from(u64::MAX));
+        }
+
+        contract._add_stake(id8, U256::from(100), 1).unwrap_or_default();
+        contract._add_stake(id8, U256::from(50), 1).unwrap_or_default();
+        assert!(matches!(
+            contract._add_stake(id8, U256::from(10), 2),
+            Err(Error::TeamLocked(_))
+        ));
+
+        let event = contract.events.get(id8);
+        assert_eq!(event.total.get(0).unwrap_or_default(), U256::from(150));
+        assert_eq!(event.total.get(1).unwrap_or_default(), U256::ZERO);
+        assert_eq!(event.player.get(player).stake.get(), Uint::<128, 2>::from(150));
+        assert_eq!(contract.player_events.get(player).len(), 1);
+        assert_eq!(contract.stakes.get(id8).len(), 2);
+    }
+
+    #[motsu::test]
+    fn bet_slip(contract: StakeEngine) {
+        assert!(matches!(
+            contract.set_bet_slip(address!("7e32b54800705876d3b5cfbc7d9c226a211f7c1a")),
+            Err(Error::UnauthorizedAccount(_))
+        ));
+
+        contract._owner.set(msg::sender());
+        let bet_slip = address!("7e32b54800705876d3b5cfbc7d9c226a211f7c1a");
+        assert!(contract.set_bet_slip(bet_slip).unwrap_or_default());
+        assert_eq!(contract.bet_slip(), bet_slip);
+
+        assert_eq!(contract.slip_position(U256::from(1)), (String::new(), Address::ZERO));
+        assert!(matches!(
+            contract.pay_slip(U256::from(1)),
+            Err(Error::UnknownSlip(_))
+        ));
+    }
+
+    #[motsu::test]
+    fn parlay(contract: StakeEngine) {
+        let one = U256::from(ODDS_PRECISION);
+        let first = crate::tools::string_to_bytes8("WXHG1234");
+        let second = crate::tools::string_to_bytes8("WXHG5678");
+
+        // 2.0 x 1.5 on 100.
+        let odds = [one * U256::from(2), one * U256::from(3) / U256::from(2)];
+        assert_eq!(StakeEngine::_parlay_payout(U256::from(100), &odds), U256::from(300));
+        assert_eq!(StakeEngine::_parlay_payout(U256::from(100), &[]), U256::from(100));
+
+        // An empty side cannot be priced.
+        assert!(matches!(
+            contract._leg_odds(first, 1),
+            Err(Error::LegNotPriced(_))
+        ));
+        {
+            let mut event = contract.events.setter(first);
+            event.total.setter(0).unwrap().set(U256::from(300));
+            event.total.setter(1).unwrap().set(U256::from(600));
+        }
+        assert_eq!(
+            contract._leg_odds(first, 1).unwrap_or_default(),
+            one * U256::from(882) / U256::from(300)
+        );
+
+        // An open ticket on both events, backed by the reserve.
+        let ticket_id = U256::from(1);
+        {
+            let mut ticket = contract.parlays.setter(ticket_id);
+            ticket.player.set(msg::sender());
+            ticket.amount.set(U256::from(100));
+            ticket.potential_payout.set(U256::from(300));
+            ticket.status.set(Uint::<8, 1>::from(1u8));
+            for (event_id, team, leg_odds) in [(first, 1u8, odds[0]), (second, 2u8, odds[1])] {
+                let mut leg = ticket.legs.grow();
+                leg.event_id.set(event_id);
+                leg.team.set(Uint::<8, 1>::from(team));
+                leg.odds.set(leg_odds);
+            }
+            let mut pool = contract.collateral_pools.setter(Address::ZERO);
+            pool.parlay_reserve.set(U256::from(1_000));
+            pool.parlay_liability.set(U256::from(300));
+        }
+
+        // Not settled until every leg is closed.
+        contract.events.setter(first).status.set(Uint::<8, 1>::from(2u8));
+        contract.events.setter(first).winner.set(Uint::<8, 1>::from(3u8));
+        assert!(matches!(
+            contract.settle_parlay(ticket_id),
+            Err(Error::WrongStatus(_))
+        ));
+
+        // The first leg is voided and dropped, the second is lost.
+        contract.events.setter(second).status.set(Uint::<8, 1>::from(2u8));
+        contract.events.setter(second).winner.set(Uint::<8, 1>::from(1u8));
+        assert_eq!(contract.settle_parlay(ticket_id).unwrap_or_default(), U256::ZERO);
+        assert_eq!(
+            contract.parlay_reserve(Address::ZERO),
+            (U256::from(1_000), U256::ZERO)
+        );
+        let (wins, losses, _, staked, _, _, _) = contract.get_player_record(msg::sender());
+        assert_eq!((wins, losses, staked), (0, 1, U256::from(100)));
+        assert!(matches!(
+            contract.settle_parlay(ticket_id),
+            Err(Error::ParlayNotOpen(_))
+        ));
+
+        let (player, amount, _, status, _, legs) = contract.get_parlay(ticket_id);
+        assert_eq!(player, msg::sender());
+        assert_eq!(amount, U256::from(100));
+        assert_eq!(status, 2);
+        assert_eq!(legs.len(), 2);
+        assert_eq!(legs[1], ("WXHG5678".to_string(), 2, odds[1]));
+
+        // A voided leg priced under 1.0 cannot lift the payout above what was reserved.
+        let ticket_id = U256::from(2);
+        {
+            let mut ticket = contract.parlays.setter(ticket_id);
+            ticket.player.set(msg::sender());
+            ticket.amount.set(U256::from(100));
+            ticket.potential_payout.set(U256::from(100));
+            ticket.status.set(Uint::<8, 1>::from(1u8));
+            let legs = [
+                (first, 1u8, one / U256::from(2)),
+                (second, 1u8, one * U256::from(2)),
+            ];
+            for (event_id, team, leg_odds) in legs {
+                let mut leg = ticket.legs.grow();
+                leg.event_id.set(event_id);
+                leg.team.set(Uint::<8, 1>::from(team));
+                leg.odds.set(leg_odds);
+            }
+            contract
+                .collateral_pools
+                .setter(Address::ZERO)
+                .parlay_liability
+                .set(U256::from(100));
+        }
+        let _ = contract.settle_parlay(ticket_id);
+        assert_eq!(
+            contract.parlay_reserve(Address::ZERO),
+            (U256::from(900), U256::ZERO)
+        );
+
+        // A reserve that cannot cover the ticket is rejected instead of wrapping.
+        let ticket_id = U256::from(3);
+        {
+            let mut ticket = contract.parlays.setter(ticket_id);
+            ticket.amount.set(U256::from(100));
+            ticket.potential_payout.set(U256::from(2_000));
+            ticket.status.set(Uint::<8, 1>::from(1u8));
+            let mut leg = ticket.legs.grow();
+            leg.event_id.set(second);
+            leg.team.set(Uint::<8, 1>::from(1u8));
+            leg.odds.set(one * U256::from(20));
+        }
+        assert!(matches!(
+            contract.settle_parlay(ticket_id),
+            Err(Error::InsufficientParlayReserve(_))
+        ));
+        assert_eq!(contract.get_parlay(ticket_id).3, 1);
+    }
+
+    #[motsu::test]
+    fn score_markets(contract: StakeEngine) {
+        let event_id = "WXHG1234".to_string();
+
+        // Only the owner or the oracle can create markets.
+        assert!(contract.create_market(event_id.clone(), MARKET_TOTALS, 25).is_err());
+        contract._owner.set(msg::sender());
+        assert!(matches!(
+            contract.create_market(event_id.clone(), 4, 0),
+            Err(Error::InvalidMarketKind(_))
+        ));
+
+        // Totals on 2.5 points and Team A giving 1.5 points.
+        assert_eq!(StakeEngine::_market_outcome(MARKET_TOTALS, 25, 2, 1), Some(1));
+        assert_eq!(StakeEngine::_market_outcome(MARKET_TOTALS, 25, 1, 1), Some(2));
+        assert_eq!(StakeEngine::_market_outcome(MARKET_TOTALS, 30, 2, 1), None);
+        assert_eq!(StakeEngine::_market_outcome(MARKET_SPREAD, -15, 2, 0), Some(1));
+        assert_eq!(StakeEngine::_market_outcome(MARKET_SPREAD, -15, 2, 1), Some(2));
+        assert_eq!(StakeEngine::_market_outcome(MARKET_SPREAD, -10, 2, 1), None);
+        assert_eq!(
+            StakeEngine::_market_outcome(MARKET_EXACT_SCORE, 0, 2, 1),
+            Some((2 << 16) | 1)
+        );
+
+        // Markets only open on events still accepting stakes.
+        let id8 = FixedBytes::<8>::from(*b"WXHG1234");
+        assert!(matches!(
+            contract.create_market(event_id.clone(), MARKET_TOTALS, 25),
+            Err(Error::EventNotFound(_))
+        ));
+        assert!(matches!(
+            contract._create_market(id8, MARKET_TOTALS, 25),
+            Err(Error::WrongStatus(_))
+        ));
+        {
+            let mut event = contract.events.setter(id8);
+            event.status.set(Uint::<8, 1>::from(1u8));
+            event.start_date.set(Uint::<64, 1>::from(block::timestamp()));
+        }
+        assert!(matches!(
+            contract._create_market(id8, MARKET_TOTALS, 25),
+            Err(Error::AlreadyStarted(_))
+        ));
+        contract
+            .events
+            .setter(id8)
+            .start_date
+            .set(Uint::<64, 1>::from(block::timestamp() + 3_600));
+
+        let market_id = contract
+            ._create_market(id8, MARKET_TOTALS, 25)
+            .unwrap_or_default();
+        assert_eq!(market_id, U256::from(1));
+        assert_eq!(
+            contract.get_market(market_id),
+            (event_id.clone(), MARKET_TOTALS, 25, 1, 0, U256::ZERO)
+        );
+
+        // 300 on over, 100 on under.
+        let under = address!("7e32b54800705876d3b5cfbc7d9c226a211f7c1a");
+        {
+            let mut market = contract.markets.setter(market_id);
+            market.total.set(U256::from(400));
+            market.pools.setter(Uint::<32, 1>::from(1)).set(U256::from(300));
+            market.pools.setter(Uint::<32, 1>::from(2)).set(U256::from(100));
+            let mut position = market.positions.setter(msg::sender());
+            position.outcome.set(Uint::<32, 1>::from(1));
+            position.amount.set(U256::from(300));
+            let mut position = market.positions.setter(under);
+            position.outcome.set(Uint::<32, 1>::from(2));
+            position.amount.set(U256::from(100));
+        }
+        assert_eq!(contract.market_pool(market_id, 1), U256::from(300));
+        assert_eq!(
+            contract.market_position(market_id, under),
+            (2, U256::from(100), false)
+        );
+
+        // Positions cannot be claimed before resolution; 3-1 goes over the line.
+        assert!(matches!(
+            contract.claim_market(market_id, under),
+            Err(Error::MarketWrongStatus(_))
+        ));
+        contract._resolve_market(market_id, Some((3, 1)));
+        let (_, _, _, status, winning_outcome, _) = contract.get_market(market_id);
+        assert_eq!((status, winning_outcome), (2, 1));
+        assert!(contract.markets.get(market_id).commission.get() > U256::ZERO);
+
+        // The losing side is paid nothing and cannot claim twice.
+        assert_eq!(contract.claim_market(market_id, under).unwrap_or_default(), U256::ZERO);
+        assert!(matches!(
+            contract.claim_market(market_id, under),
+            Err(Error::NothingToClaim(_))
+        ));
+        let (_, losses, _, staked, payout, _, _) = contract.get_player_record(under);
+        assert_eq!((losses, staked, payout), (1, U256::from(100), U256::ZERO));
+
+        // A push voids the market.
+        let push_id = contract
+            .create_market(event_id, MARKET_TOTALS, 40)
+            .unwrap_or_default();
+        contract._resolve_market(push_id, Some((3, 1)));
+        let (_, _, _, status, _, _) = contract.get_market(push_id);
+        assert_eq!(status, 3);
+    }
+
+    #[motsu::test]
+    fn outrights(contract: StakeEngine) {
+        let usdc = address!("7e32b54800705876d3b5cfbc7d9c226a211f7c1a");
+        let player = address!("3f1eae7d46d88f08fc2f8ed27fcb2ab183eb2d0e");
+        let close_time = block::timestamp() + 86_400;
+        let labels: Vec<String> = ["Arsenal", "Chelsea", "Liverpool", "Everton"]
+            .iter()
+            .map(|label| label.to_string())
+            .collect();
+
+        contract._owner.set(msg::sender());
+        assert!(matches!(
+            contract.create_outright(usdc, close_time, labels.clone()),
+            Err(Error::CollateralNotAllowed(_))
+        ));
+        contract._set_collateral(usdc, true);
+        assert!(matches!(
+            contract.create_outright(usdc, close_time, vec!["Arsenal".to_string()]),
+            Err(Error::InvalidOutcomeCount(_))
+        ));
+        assert!(matches!(
+            contract.create_outright(usdc, close_time, vec![String::new(); MAX_OUTRIGHT_OUTCOMES + 1]),
+            Err(Error::InvalidOutcomeCount(_))
+        ));
+        assert!(matches!(
+            contract.create_outright(usdc, block::timestamp(), labels.clone()),
+            Err(Error::DeadlineExpired(_))
+        ));
+
+        let outright_id = contract
+            .create_outright(usdc, close_time, labels)
+            .unwrap_or_default();
+        assert_eq!(outright_id, U256::from(1));
+
+        // 600 on Arsenal, 200 on Chelsea and 200 on Liverpool.
+        contract._add_outright_stake(outright_id, msg::sender(), 0, U256::from(600));
+        contract._add_outright_stake(outright_id, player, 1, U256::from(200));
+        contract._add_outright_stake(outright_id, player, 2, U256::from(200));
+        assert_eq!(contract.outright_position(outright_id, player, 2), U256::from(200));
+
+        let page = contract.get_outright_outcomes(outright_id, 2, 5);
+        assert_eq!(
+            page,
+            vec![
+                (2, "Liverpool".to_string(), U256::from(200)),
+                (3, "Everton".to_string(), U256::ZERO),
+            ]
+        );
+        assert!(contract.get_outright_outcomes(outright_id, 9, 5).is_empty());
+
+        // No settlement while stakes are still accepted.
+        assert!(matches!(
+            contract._settle_outright(outright_id, &[1]),
+            Err(Error::OutrightNotClosed(_))
+        ));
+        contract
+            .outrights
+            .setter(outright_id)
+            .close_time
+            .set(Uint::<64, 1>::from(block::timestamp()));
+
+        // Winners must be distinct outcomes of the outright.
+        assert!(matches!(
+            contract._settle_outright(outright_id, &[1, 1]),
+            Err(Error::InvalidOutcome(_))
+        ));
+        assert!(matches!(
+            contract._settle_outright(outright_id, &[4]),
+            Err(Error::InvalidOutcome(_))
+        ));
+
+        // Chelsea and Everton dead-heat: Everton was not backed, so Chelsea takes the pool.
+        assert!(contract._settle_outright(outright_id, &[1, 3]).unwrap_or_default());
+        let (_, _, status, outcome_count, total, winners) = contract.get_outright(outright_id);
+        assert_eq!((status, outcome_count, total), (2, 4, U256::from(1000)));
+        assert_eq!(winners, vec![1, 3]);
+        assert_eq!(contract.outrights.get(outright_id).funded_winners.get(), Uint::<8, 1>::from(1));
+
+        assert_eq!(
+            contract.claim_outright(outright_id, msg::sender()).unwrap_or_default(),
+            U256::ZERO
+        );
+        assert!(matches!(
+            contract.claim_outright(outright_id, msg::sender()),
+            Err(Error::NothingToClaim(_))
+        ));
+        let (_, losses, _, staked, _, _, net) = contract.get_player_record(msg::sender());
+        assert_eq!((losses, staked), (1, U256::from(600)));
+        assert_eq!(net, I256::try_from(-600i64).unwrap());
+
+        // The winner is credited with the whole pool, net of the commission they bore.
+        let _ = contract.claim_outright(outright_id, player);
+        let (wins, _, _, staked, payout, commission, _) = contract.get_player_record(player);
+        assert_eq!((wins, staked), (1, U256::from(400)));
+        assert_eq!(payout + commission, U256::from(1000));
+        assert!(matches!(
+            contract._settle_outright(outright_id, &[0]),
+            Err(Error::OutrightWrongStatus(_))
+        ));
+
+        // Two backed winners split the distributable pool equally.
+        assert_eq!(
+            StakeEngine::_dead_heat_payout(U256::from(100), U256::from(200), U256::from(900), 2),
+            U256::from(225)
+        );
+        assert_eq!(
+            StakeEngine::_dead_heat_payout(U256::from(100), U256::ZERO, U256::from(900), 2),
+            U256::ZERO
+        );
+    }
+
+    #[motsu::test]
+    fn player_history(contract: StakeEngine) {
+        let player = address!("3f1eae7d46d88f08fc2f8ed27fcb2ab183eb2d0e");
+        let won = FixedBytes::<8>::from(*b"WXHG1234");
+        let lost = FixedBytes::<8>::from(*b"ABCR3570");
+        let refunded = FixedBytes::<8>::from(*b"XGTY8844");
+
+        assert_eq!(contract.player_history_count(player), 0);
+        contract._record_history(player, won, 1, U256::from(100), OUTCOME_WON, U256::from(180), U256::from(2));
+        contract._record_history(player, lost, 2, U256::from(150), OUTCOME_LOST, U256::ZERO, U256::ZERO);
+        contract._record_history(player, refunded, 1, U256::from(50), OUTCOME_REFUNDED, U256::from(50), U256::ZERO);
+
+        // Entries are kept oldest first and paginated by offset and limit.
+        assert_eq!(contract.player_history_count(player), 3);
+        let page = contract.get_player_history(player, 1, 1);
+        assert_eq!(
+            page,
+            vec![(
+                "ABCR3570".to_string(),
+                2,
+                U256::from(150),
+                OUTCOME_LOST,
+                U256::ZERO,
+                U256::ZERO,
+                block::timestamp()
+            )]
+        );
+        assert_eq!(contract.get_player_history(player, 2, 10).len(), 1);
+        assert!(contract.get_player_history(player, 3, 10).is_empty());
+        assert!(contract.get_player_history(msg::sender(), 0, 10).is_empty());
+
+        // 300 staked for 230 paid out: a net loss of 70.
+        let (wins, losses, refunds, staked, payout, commission, net) =
+            contract.get_player_record(player);
+        assert_eq!((wins, losses, refunds), (1, 1, 1));
+        assert_eq!((staked, payout, commission), (U256::from(300), U256::from(230), U256::from(2)));
+        assert_eq!(net, I256::try_from(-70i64).unwrap());
+    }
+
+    #[motsu::test]
+    fn paginated_views(contract: StakeEngine) {
+        let event_id = "WXHG1234".to_string();
+        let id8 = crate::tools::string_to_bytes8(&event_id);
+        {
+            let mut event = contract.events.setter(id8);
+            event.status.set(Uint::<8, 1>::from(1u8));
+            event.start_date.set(Uint::<64, 1>::from(u64::MAX));
+        }
+        for amount in [100u64, 200, 300] {
+            contract._add_stake(id8, U256::from(amount), 1).unwrap_or_default();
+        }
+
+        // Newest first, with the total and the offset of the next page.
+        let (stakes, total, next) = contract.get_stakes(event_id.clone(), 0, 2).unwrap_or_default();
+        assert_eq!((total, next), (3, 2));
+        assert_eq!(
+            stakes,
+            vec![
+                (msg::sender(), U256::from(300), 1, block::timestamp()),
+                (msg::sender(), U256::from(200), 1, block::timestamp()),
+            ]
+        );
+        let (stakes, _, next) = contract.get_stakes(event_id.clone(), next, 2).unwrap_or_default();
+        assert_eq!(stakes.len(), 1);
+        assert_eq!(stakes[0].1, U256::from(100));
+        assert_eq!(next, 0);
+        let (stakes, total, next) = contract.get_stakes(event_id, 5, 2).unwrap_or_default();
+        assert!(stakes.is_empty());
+        assert_eq!((total, next), (3, 0));
+
+        let (events, total, next) = contract.get_player_events(0, 10).unwrap_or_default();
+        assert_eq!((events.len(), total, next), (1, 1, 0));
+        assert_eq!(events[0].0, "WXHG1234");
+        assert_eq!(events[0].3, U256::from(600));
+
+        assert_eq!(StakeEngine::_newest_first_page(10, 0, 4), (6, 10, 4));
+        assert_eq!(StakeEngine::_newest_first_page(10, 8, 4), (0, 2, 0));
+        assert_eq!(StakeEngine::_newest_first_page(10, 0, 0), (10, 10, 0));
+    }
+
+    #[motsu::test]
+    fn player_events_set(contract: StakeEngine) {
+        let player = msg::sender();
+        let ids = [
+            FixedBytes::<8>::from(*b"WXHG1234"),
+            FixedBytes::<8>::from(*b"ABCR3570"),
+            FixedBytes::<8>::from(*b"XGTY8844"),
+        ];
+        for id in ids {
+            {
+                let mut event = contract.events.setter(id);
+                event.status.set(Uint::<8, 1>::from(1u8));
+                event.start_date.set(Uint::<64, 1>::from(u64::MAX));
+            }
+            contract._add_stake(id, U256::from(100), 1).unwrap_or_default();
+        }
+        assert_eq!(contract.player_events.get(player).len(), 3);
+
+        // Removal swaps the last event into the freed slot.
+        contract._remove_event(ids[0], player).unwrap_or_default();
+        let events = contract.player_events.get(player);
+        assert_eq!(events.len(), 2);
+        assert!(!events.contains(ids[0]));
+        assert_eq!(events.get(0), Some(ids[2]));
+        assert!(matches!(
+            contract._remove_event(ids[0], player),
+            Err(Error::PlayerEventNotFound(_))
+        ));
+    }
+
+    /// Linear-scan removal that `EventIdSet` replaced, kept to benchmark the two in one run.
+    fn scan_remove(ids: &mut StorageVec<StorageFixedBytes<8>>, id: FixedBytes<8>) -> bool {
+        let Some(index) = (0..ids.len()).find(|&i| ids.get(i) == Some(id)) else {
+            return false;
+        };
+        let last_index = ids.len() - 1;
+        if index < last_index {
+            if let (Some(last_id), Some(mut slot)) = (ids.get(last_index), ids.setter(index)) {
+                slot.set(last_id);
+            }
+        }
+        ids.pop();
+        true
+    }
+
+    /// Time of `rounds` removals of the newest of `size` player positions, as (scan, set).
+    fn time_removals(contract: &mut StakeEngine, size: u64, rounds: u32) -> (Duration, Duration) {
+        let player = msg::sender();
+        // Far away from the contract's own slots.
+        let mut scanned = unsafe { StorageVec::<StorageFixedBytes<8>>::new(U256::from(u64::MAX), 0) };
+        for i in 0..size {
+            let id = FixedBytes::<8>::from(i.to_be_bytes());
+            scanned.push(id);
+            contract.player_events.setter(player).insert(id);
+        }
+        let newest = FixedBytes::<8>::from((size - 1).to_be_bytes());
+
+        let started = Instant::now();
+        for _ in 0..rounds {
+            assert!(scan_remove(&mut scanned, newest));
+            scanned.push(newest);
+        }
+        let scan = started.elapsed();
+
+        let started = Instant::now();
+        for _ in 0..rounds {
+            assert!(contract._remove_event(newest, player).is_ok());
+            contract.player_events.setter(player).insert(newest);
+        }
+        (scan, started.elapsed())
+    }
+
+    #[test]
+    fn event_id_set_copies_match() {
+        assert_eq!(
+            include_str!("../event_id_set.rs"),
+            include_str!("../../../stylus_core_events/src/event_id_set.rs"),
+            "the vendored EventIdSet copies have drifted apart"
+        );
+    }
+
+    #[motsu::test]
+    fn player_events_outpace_scan(contract: StakeEngine) {
+        // The scan grows with the number of open positions, the set does not.
+        let (scan, set) = time_removals(contract, 512, 20);
+        assert!(set < scan, "set {:?} should beat scan {:?}", set, scan);
+    }
+
+    #[motsu::test]
+    fn packed_layout(contract: StakeEngine) {
+        assert_eq!(<Player as StorageType>::REQUIRED_SLOTS, 2);
+        assert_eq!(<Stake as StorageType>::REQUIRED_SLOTS, 2);
+
+        let id8 = FixedBytes::<8>::from(*b"WXHG1234");
+        let player = msg::sender();
+
+        // Fields sharing a slot must not clobber each other.
+        {
+            let mut event = contract.events.setter(id8);
+            let mut position = event.player.setter(player);
+            position.stake.set(Uint::<128, 2>::MAX);
+            position.team.set(Uint::<8, 1>::from(2u8));
+            position.paid.set(true);
+            position.slip_id.set(U256::MAX);
+            position.team.set(Uint::<8, 1>::from(1u8));
+        }
+        let event = contract.events.get(id8);
+        let position = event.player.get(player);
+        assert_eq!(position.stake.get(), Uint::<128, 2>::MAX);
+        assert_eq!(position.team.get(), Uint::<8, 1>::from(1u8));
+        assert!(position.paid.get());
+        assert_eq!(position.slip_id.get(), U256::MAX);
+
+        {
+            let mut stakes = contract.stakes.setter(id8);
+            let mut stake = stakes.grow();
+            stake.player.set(player);
+            stake.team.set(Uint::<8, 1>::from(2u8));
+            stake.timestamp.set(Uint::<64, 1>::from(u64::MAX));
+            stake.amount.set(U256::MAX);
+        }
+        let (stakes, _, _) = contract.get_stakes("WXHG1234".to_string(), 0, 1).unwrap_or_default();
+        assert_eq!(stakes, vec![(player, U256::MAX, 2, u64::MAX)]);
+
+        // Positions are capped to the packed 128-bit range.
+        {
+            let mut event = contract.events.setter(id8);
+            event.status.set(Uint::<8, 1>::from(1u8));
+            event.start_date.set(Uint::<64, 1>::from(u64::MAX));
+        }
+        assert!(matches!(
+            contract._add_stake(id8, U256::from(1), 1),
+            Err(Error::StakeOverflow(_))
+        ));
+    }
+
+    #[motsu::test]
+    fn player_controls(contract: StakeEngine) {
+        let player = msg::sender();
+        let usdc = address!("7e32b54800705876d3b5cfbc7d9c226a211f7c1a");
+        let now = block::timestamp();
+
+        assert!(matches!(
+            contract.set_spend_limit(usdc, 3, U256::from(100)),
+            Err(Error::InvalidPeriod(_))
+        ));
+
+        // A first limit and a lower one apply immediately.
+        assert_eq!(contract.set_spend_limit(usdc, PERIOD_WEEKLY, U256::from(500)).unwrap_or_default(), now);
+        assert_eq!(contract.set_spend_limit(usdc, PERIOD_DAILY, U256::from(200)).unwrap_or_default(), now);
+        assert_eq!(contract.set_spend_limit(usdc, PERIOD_DAILY, U256::from(100)).unwrap_or_default(), now);
+
+        contract._charge_player_controls(player, usdc, U256::from(60)).unwrap_or_default();
+        assert!(matches!(
+            contract._charge_player_controls(player, usdc, U256::from(50)),
+            Err(Error::SpendLimitExceeded(_))
+        ));
+        assert_eq!(
+            contract.spend_limit(player, usdc, PERIOD_DAILY).unwrap_or_default(),
+            (U256::from(100), U256::from(60), U256::ZERO, 0)
+        );
+        assert_eq!(
+            contract.spend_limit(player, usdc, PERIOD_MONTHLY).unwrap_or_default(),
+            (U256::ZERO, U256::from(60), U256::ZERO, 0)
+        );
+        // Limits are kept per collateral.
+        assert!(contract._charge_player_controls(player, Address::ZERO, U256::from(1_000)).is_ok());
+
+        // Raising a limit waits for the cooling-off.
+        let effective_at = contract
+            .set_spend_limit(usdc, PERIOD_DAILY, U256::from(1_000))
+            .unwrap_or_default();
+        assert_eq!(effective_at, now + LIMIT_COOLING_OFF);
+        assert_eq!(
+            contract.spend_limit(player, usdc, PERIOD_DAILY).unwrap_or_default(),
+            (U256::from(100), U256::from(60), U256::from(1_000), effective_at)
+        );
+        assert!(contract._charge_player_controls(player, usdc, U256::from(50)).is_err());
+
+        // Self-exclusion blocks new stakes and cannot be shortened.
+        let until = contract.self_exclude(86_400).unwrap_or_default();
+        assert_eq!(until, now + 86_400);
+        assert_eq!(contract.self_exclude(60).unwrap_or_default(), until);
+        assert_eq!(contract.excluded_until(player), until);
+        assert!(matches!(
+            contract._charge_player_controls(player, usdc, U256::from(1)),
+            Err(Error::SelfExcluded(_))
+        ));
+    }
+
+    #[motsu::test]
+    fn stake_orders(contract: StakeEngine) {
+        use ethers::signers::{LocalWallet, Signer};
+        use ethers::types::transaction::eip712::{Eip712, TypedData};
+
+        let wallet: LocalWallet = "0x4c0883a69102937d6231471b5dbb6204fe512961708279f5b0f1b1d0c1d8f5e3"
+            .parse()
+            .expect("Should parse a private key");
+        let player = Address::from(wallet.address().0);
+        let engine = address!("a6e41ffd769491a42a6e5ce453259b93983a22ef");
+        let order: StakeOrder = (
+            "WXHG1234".to_string(),
+            2,
+            U256::from(1_000),
+            U256::from(7),
+            1_735_700_000,
+            player,
+        );
+
+        // The digest matches an independent EIP-712 encoder.
+        let typed_data: TypedData = serde_json::from_value(serde_json::json!({
+            "types": {
+                "EIP712Domain": [
+                    {"name": "name", "type": "string"},
+                    {"name": "version", "type": "string"},
+                    {"name": "chainId", "type": "uint256"},
+                    {"name": "verifyingContract", "type": "address"}
+                ],
+                "StakeOrder": [
+                    {"name": "event_id", "type": "string"},
+                    {"name": "team", "type": "uint8"},
+                    {"name": "amount", "type": "uint256"},
+                    {"name": "nonce", "type": "uint256"},
+                    {"name": "deadline", "type": "uint64"},
+                    {"name": "player", "type": "address"}
+                ]
+            },
+            "primaryType": "StakeOrder",
+            "domain": {
+                "name": crate::EIP712_NAME,
+                "version": crate::EIP712_VERSION,
+                "chainId": 42161,
+                "verifyingContract": format!("{engine:?}")
+            },
+            "message": {
+                "event_id": "WXHG1234",
+                "team": 2,
+                "amount": "1000",
+                "nonce": "7",
+                "deadline": 1_735_700_000u64,
+                "player": format!("{player:?}")
+            }
+        }))
+        .expect("Should parse typed data");
+        let expected = typed_data.encode_eip712().expect("Should encode typed data");
+        let digest = StakeEngine::_order_digest(StakeEngine::_domain_separator(42161, engine), &order);
+        assert_eq!(digest.0, expected);
+
+        // Signatures are split as r || s || v and must not be malleable.
+        let signature = wallet
+            .sign_hash(ethers::types::H256(expected))
+            .expect("Should sign the digest");
+        let bytes = signature.to_vec();
+        let (_, _, v) = StakeEngine::_split_signature(&bytes).expect("Should accept the signature");
+        assert_eq!(v as u64, signature.v);
+        assert!(StakeEngine::_split_signature(&bytes[..64]).is_none());
+        let mut malleable = bytes.clone();
+        malleable[32..64].copy_from_slice(&[0xff; 32]);
+        assert!(StakeEngine::_split_signature(&malleable).is_none());
+
+        // Nonces are single use, whether filled or canceled.
+        assert!(!contract.nonce_used(msg::sender(), U256::from(7)));
+        assert!(contract.cancel_nonce(U256::from(7)).unwrap_or_default());
+        assert!(contract.nonce_used(msg::sender(), U256::from(7)));
+        assert!(matches!(
+            contract.cancel_nonce(U256::from(7)),
+            Err(Error::NonceUsed(_))
+        ));
+    }
+
+    #[motsu::test]
+    fn referrals(contract: StakeEngine) {
+        let referrer = address!("3f1eae7d46d88f08fc2f8ed27fcb2ab183eb2d0e");
+        let aton: Address = ATON_ADDRESS.parse().expect("Should parse valid hex address");
+        let player = msg::sender();
+        contract._set_vault_aton(aton, Address::ZERO);
+        contract._owner.set(player);
+
+        assert!(matches!(
+            contract.bind_referrer(player),
+            Err(Error::InvalidReferrer(_))
+        ));
+        assert!(contract.bind_referrer(referrer).unwrap_or_default());
+        assert!(matches!(
+            contract.bind_referrer(aton),
+            Err(Error::ReferrerAlreadySet(_))
+        ));
+        assert_eq!(contract.referrer_of(player), referrer);
+
+        assert!(matches!(
+            contract.set_referral_share(U256::from(crate::PCT_DENOM + 1)),
+            Err(Error::InvalidShare(_))
+        ));
+        contract
+            .set_referral_share(U256::from(crate::PCT_DENOM / 2))
+            .unwrap_or_default();
+
+        // Only ATON stakes count; a stake accrues half of the 2% it pays into the commission.
+        let id8 = FixedBytes::<8>::from(*b"WXHG1234");
+        contract._record_referral_volume(player, id8, aton, U256::from(1_000));
+        contract._record_referral_volume(player, id8, referrer, U256::from(1_000));
+        assert_eq!(
+            contract.referrer_stats(referrer),
+            (U256::from(1), U256::from(1_000), U256::ZERO, U256::ZERO, U256::from(10))
+        );
+        assert_eq!(
+            contract.event_referral_rewards("WXHG1234".to_string(), referrer),
+            U256::from(10)
+        );
+
+        // Held until the event settles, whichever side the player backed.
+        assert!(matches!(
+            contract.release_referral_rewards("WXHG1234".to_string(), referrer),
+            Err(Error::WrongStatus(_))
+        ));
+        {
+            let mut event = contract.events.setter(id8);
+            event.total.setter(0).unwrap().set(U256::from(6_000));
+            event.total.setter(1).unwrap().set(U256::from(4_000));
+            event.status.set(Uint::<8, 1>::from(2u8));
+            event.winner.set(Uint::<8, 1>::from(1u8));
+        }
+        assert_eq!(
+            contract
+                .release_referral_rewards("WXHG1234".to_string(), referrer)
+                .unwrap_or_default(),
+            U256::from(10)
+        );
+        assert_eq!(
+            contract.referrer_stats(referrer),
+            (U256::from(1), U256::from(1_000), U256::from(10), U256::ZERO, U256::ZERO)
+        );
+        // 10_000 staked: the 200 commission is retained once, less the referrer's share.
+        assert_eq!(contract.collateral_pools.get(aton).commission.get(), U256::from(190));
+        assert_eq!(
+            contract
+                .release_referral_rewards("WXHG1234".to_string(), referrer)
+                .unwrap_or_default(),
+            U256::ZERO
+        );
+
+        // Rewards accrued on a voided event are dropped.
+        let voided = FixedBytes::<8>::from(*b"ABCR3570");
+        contract._record_referral_volume(player, voided, aton, U256::from(500));
+        {
+            let mut event = contract.events.setter(voided);
+            event.total.setter(0).unwrap().set(U256::from(500));
+            event.total.setter(1).unwrap().set(U256::from(500));
+            event.status.set(Uint::<8, 1>::from(2u8));
+            event.winner.set(Uint::<8, 1>::from(3u8));
+        }
+        assert_eq!(
+            contract
+                .release_referral_rewards("ABCR3570".to_string(), referrer)
+                .unwrap_or_default(),
+            U256::ZERO
+        );
+        let (_, volume, earned, _, pending) = contract.referrer_stats(referrer);
+        assert_eq!((volume, earned, pending), (U256::from(1_500), U256::from(10), U256::ZERO));
+
+        // An account that earned nothing has nothing to claim.
+        assert_eq!(contract.claim_referral_rewards().unwrap_or_default(), U256::ZERO);
+        assert_eq!(StakeEngine::_referral_reward(U256::from(1_000), U256::ZERO), U256::ZERO);
+    }
+
+    #[motsu::test]
+    fn promo_credits(contract: StakeEngine) {
+        let promoter = address!("3f1eae7d46d88f08fc2f8ed27fcb2ab183eb2d0e");
+        let aton: Address = ATON_ADDRESS.parse().expect("Should parse valid hex address");
+        let owner = msg::sender();
+        contract._set_vault_aton(aton, Address::ZERO);
+        contract._owner.set(owner);
+        let now = block::timestamp();
+
+        // Only promoters can issue credit.
+        assert!(matches!(
+            contract.issue_credit(owner, U256::from(100), now + 10),
+            Err(Error::UnauthorizedAccount(_))
+        ));
+        contract.set_promoter(promoter, true).unwrap_or_default();
+        assert!(contract.is_promoter(promoter));
+
+        // Outstanding credit is bounded by the cap, then by the reserve.
+        contract.set_promo_credit_cap(U256::from(500)).unwrap_or_default();
+        assert!(matches!(
+            contract._issue_credit(owner, U256::from(600), now + 10),
+            Err(Error::CreditCapExceeded(_))
+        ));
+        assert!(matches!(
+            contract._issue_credit(owner, U256::from(100), now + 10),
+            Err(Error::InsufficientPromoReserve(_))
+        ));
+        contract.promo_reserve.set(U256::from(1_000));
+        assert!(matches!(
+            contract._issue_credit(owner, U256::from(100), now),
+            Err(Error::DeadlineExpired(_))
+        ));
+
+        contract._issue_credit(owner, U256::from(100), now + 100).unwrap_or_default();
+        contract._issue_credit(owner, U256::from(50), now + 10).unwrap_or_default();
+        assert_eq!(contract.credit_balance(owner), U256::from(150));
+        assert_eq!(
+            contract.promo_summary(),
+            (U256::from(1_000), U256::from(150), U256::from(500))
+        );
+
+        // The reserve backing outstanding credit cannot be withdrawn.
+        assert!(matches!(
+            contract.withdraw_promo_reserve(U256::from(900), owner),
+            Err(Error::InsufficientPromoReserve(_))
+        ));
+
+        // Credit is spent soonest-expiring first.
+        assert!(matches!(
+            contract._take_credit(owner, U256::from(200)),
+            Err(Error::InsufficientCredit(_))
+        ));
+        contract._take_credit(owner, U256::from(70)).unwrap_or_default();
+        assert_eq!(contract.get_credits(owner), vec![(U256::from(80), now + 100)]);
+        assert_eq!(contract.promo_outstanding.get(), U256::from(80));
+
+        // Expired grants are pruned and release the outstanding credit.
+        {
+            let mut credits = contract.promo_credits.setter(owner);
+            let mut grant = credits.grow();
+            grant.amount.set(U256::from(30));
+            grant.expires_at.set(Uint::<64, 1>::from(now));
+        }
+        contract.promo_outstanding.set(U256::from(110));
+        assert_eq!(contract.credit_balance(owner), U256::from(80));
+        assert_eq!(contract.expire_credits(owner), U256::from(30));
+        assert_eq!(contract.promo_outstanding.get(), U256::from(80));
+        assert_eq!(contract.promo_credits.get(owner).len(), 1);
+
+        // Credit is only accepted on ATON events.
+        let id8 = FixedBytes::<8>::from(*b"WXHG1234");
+        contract.events.setter(id8).collateral.set(promoter);
+        assert!(matches!(
+            contract._stake_credit(owner, id8, U256::from(10), 1),
+            Err(Error::CreditNotAccepted(_))
+        ));
+
+        // Winners keep the net winnings, the credited stake returns to the reserve.
+        assert_eq!(
+            StakeEngine::_net_of_credit(U256::from(250), U256::from(100)),
+            (U256::from(150), U256::from(100))
+        );
+        // A voided credit stake refunds only the cash part.
+        assert_eq!(
+            StakeEngine::_net_of_credit(U256::from(100), U256::from(60)),
+            (U256::from(40), U256::from(60))
+        );
+        assert_eq!(
+            StakeEngine::_net_of_credit(U256::ZERO, U256::from(60)),
+            (U256::ZERO, U256::ZERO)
+        );
+    }
+
+    #[motsu::test]
+    fn house_liquidity(contract: StakeEngine) {
+        let provider = msg::sender();
+        let aton: Address = ATON_ADDRESS.parse().expect("Should parse valid hex address");
+        contract._set_vault_aton(aton, Address::ZERO);
+        contract._owner.set(provider);
+
+        // Only liquidity providers can seed.
+        assert!(matches!(
+            contract.seed_event("WXHG1234".to_string(), U256::from(100), U256::from(100)),
+            Err(Error::UnauthorizedAccount(_))
+        ));
+        contract.set_liquidity_provider(provider, true).unwrap_or_default();
+        assert!(contract.is_liquidity_provider(provider));
+
+        // A seeded event prices both sides, so the commission is no longer waived.
+        let id8 = FixedBytes::<8>::from(*b"WXHG1234");
+        contract
+            ._seed_event(provider, id8, U256::from(1_000), U256::from(1_000))
+            .unwrap_or_default();
+        contract.events.setter(id8).total.setter(0).unwrap().set(U256::from(4_000));
+        assert_eq!(
+            contract.event_liquidity("WXHG1234".to_string()),
+            (U256::from(1_000), U256::from(1_000))
+        );
+        let (waived, total, commission) = StakeEngine::_commission_for_pools(U256::from(4_000), U256::from(1_000));
+        assert!(!waived);
+        assert_eq!(total, U256::from(5_000));
+
+        // The seed is kept out of the player counters.
+        assert_eq!(contract.collateral_pools.get(aton).total_staked.get(), U256::ZERO);
+        assert_eq!(
+            contract.liquidity_summary(provider),
+            (U256::from(2_000), U256::ZERO, U256::from(2_000))
+        );
+
+        // Liquidity comes back only once the event is settled.
+        assert!(matches!(
+            contract._withdraw_liquidity(provider, id8),
+            Err(Error::WrongStatus(_))
+        ));
+        {
+            let mut event = contract.events.setter(id8);
+            event.status.set(Uint::<8, 1>::from(2u8));
+            event.winner.set(Uint::<8, 1>::from(2u8));
+        }
+
+        // Team 2 wins: the 1_000 seed is the whole winning pool of 4_900 net of commission.
+        assert_eq!(commission, U256::from(100));
+        assert_eq!(
+            contract._withdraw_liquidity(provider, id8).unwrap_or_default(),
+            U256::from(4_900)
+        );
+        assert_eq!(contract.collateral_pools.get(aton).commission.get(), U256::from(100));
+        assert_eq!(
+            contract.get_liquidity("WXHG1234".to_string(), provider),
+            (U256::from(1_000), U256::from(1_000), true)
+        );
+        assert_eq!(
+            contract.liquidity_summary(provider),
+            (U256::from(2_000), U256::from(4_900), U256::ZERO)
+        );
+        assert!(matches!(
+            contract._withdraw_liquidity(provider, id8),
+            Err(Error::NoLiquidity(_))
+        ));
+
+        // Seeds respect the event pool caps, but not the per-player stake bounds.
+        let capped = FixedBytes::<8>::from(*b"ABCR3570");
+        StakeEngine::_set_limits(
+            &mut contract.events.setter(capped).limits,
+            U256::from(500),
+            U256::from(100),
+            U256::from(1_000),
+            U256::from(200),
+        );
+        assert!(matches!(
+            contract._seed_event(provider, capped, U256::from(1_200), U256::from(1_000)),
+            Err(Error::OutcomePoolExceeded(_))
+        ));
+        assert!(matches!(
+            contract._seed_event(provider, capped, U256::from(600), U256::from(300)),
+            Err(Error::PoolImbalanceExceeded(_))
+        ));
+        assert!(contract
+            ._seed_event(provider, capped, U256::from(300), U256::from(200))
+            .unwrap_or_default());
+
+        // A self-excluded provider cannot seed.
+        contract.self_exclude(86_400).unwrap_or_default();
+        assert!(matches!(
+            contract._seed_event(provider, capped, U256::from(100), U256::from(100)),
+            Err(Error::SelfExcluded(_))
+        ));
+
+        // A voided event returns the whole seed.
+        let seed = [U256::from(300), U256::from(200)];
+        let pools = [U256::from(900), U256::from(800)];
+        assert_eq!(StakeEngine::_liquidity_return(seed, pools, 0, U256::from(34)), U256::from(500));
+        assert_eq!(StakeEngine::_liquidity_return(seed, pools, 3, U256::from(34)), U256::from(500));
+        assert_eq!(
+            StakeEngine::_liquidity_return(seed, pools, 1, U256::from(34)),
+            U256::from(300 * 1_666 / 900)
+        );
+    }
+
+    #[motsu::test]
+    fn sponsored_bonus(contract: StakeEngine) {
+        let sponsor = msg::sender();
+        let other = address!("3f1eae7d46d88f08fc2f8ed27fcb2ab183eb2d0e");
+        let id8 = FixedBytes::<8>::from(*b"WXHG1234");
+
+        contract._add_bonus(sponsor, id8, U256::from(300));
+        contract._add_bonus(other, id8, U256::from(200));
+        assert_eq!(contract.event_bonus("WXHG1234".to_string()), U256::from(500));
+        assert_eq!(
+            contract.sponsored_bonus("WXHG1234".to_string(), sponsor),
+            U256::from(300)
+        );
+
+        // Winners share the bonus pro-rata to the player stakes of their side.
+        assert_eq!(
+            StakeEngine::_bonus_share(U256::from(250), U256::from(500), U256::from(1_000)),
+            U256::from(125)
+        );
+        assert_eq!(
+            StakeEngine::_bonus_share(U256::from(250), U256::from(500), U256::ZERO),
+            U256::ZERO
+        );
+
+        // The bonus stays with the winners when a player backed the winning side.
+        {
+            let mut event = contract.events.setter(id8);
+            event.status.set(Uint::<8, 1>::from(2u8));
+            event.winner.set(Uint::<8, 1>::from(1u8));
+            event.total.setter(0).unwrap().set(U256::from(1_000));
+        }
+        assert!(matches!(
+            contract._refund_bonus(sponsor, id8),
+            Err(Error::BonusNotRefundable(_))
+        ));
+
+        // A voided event refunds each sponsor once.
+        contract.events.setter(id8).winner.set(Uint::<8, 1>::from(3u8));
+        assert_eq!(contract._refund_bonus(sponsor, id8).unwrap_or_default(), U256::from(300));
+        assert!(matches!(
+            contract._refund_bonus(sponsor, id8),
+            Err(Error::NoBonus(_))
+        ));
+        assert_eq!(contract.event_bonus("WXHG1234".to_string()), U256::from(200));
+
+        // Only house liquidity on the winning side: nobody to pay, so the bonus is refunded.
+        {
+            let mut event = contract.events.setter(id8);
+            event.winner.set(Uint::<8, 1>::from(1u8));
+            event.seeded.setter(0).unwrap().set(U256::from(1_000));
+        }
+        assert_eq!(contract._refund_bonus(other, id8).unwrap_or_default(), U256::from(200));
+        assert_eq!(contract.event_bonus("WXHG1234".to_string()), U256::ZERO);
+    }
+
+    #[motsu::test]
+    fn season_leaderboard(contract: StakeEngine) {
+        let owner = msg::sender();
+        let alice = address!("3f1eae7d46d88f08fc2f8ed27fcb2ab183eb2d0e");
+        let bob = address!("7e32b54800705876d3b5cfbc7d9c226a211f7c1a");
+        contract._owner.set(owner);
+        let season = U256::ZERO;
+
+        // Two wins then a loss: the streak resets but the best one is kept.
+        contract._record_season(alice, U256::from(100), U256::from(250), OUTCOME_WON);
+        contract._record_season(alice, U256::from(100), U256::from(150), OUTCOME_WON);
+        contract._record_season(alice, U256::from(200), U256::ZERO, OUTCOME_LOST);
+        // Refunds do not count.
+        contract._record_season(alice, U256::from(500), U256::from(500), OUTCOME_REFUNDED);
+        assert_eq!(
+            contract.season_stats(season, alice),
+            (U256::from(400), I256::ZERO, I256::ZERO, 2, 1, 0, 2)
+        );
+
+        contract._record_season(bob, U256::from(100), U256::from(200), OUTCOME_WON);
+        let (_, profit, roi, _, _, streak, _) = contract.season_stats(season, bob);
+        assert_eq!(profit, I256::try_from(100).unwrap());
+        assert_eq!(roi, I256::try_from(crate::PCT_DENOM).unwrap());
+        assert_eq!(streak, 1);
+        assert_eq!(
+            contract.get_leaderboard(season),
+            vec![
+                (bob, I256::try_from(100).unwrap(), U256::from(100)),
+                (alice, I256::ZERO, U256::from(400)),
+            ]
+        );
+
+        // A loss drops the player below the others.
+        contract._record_season(bob, U256::from(300), U256::ZERO, OUTCOME_LOST);
+        assert_eq!(contract.get_leaderboard(season)[0].0, alice);
+        assert_eq!(
+            StakeEngine::_roi(I256::try_from(-200).unwrap(), U256::from(400)),
+            I256::try_from(-(crate::PCT_DENOM as i64) / 2).unwrap()
+        );
+
+        // The leaderboard keeps the top players only.
+        for i in 0..LEADERBOARD_SIZE {
+            let player = Address::with_last_byte(i as u8 + 1);
+            contract._record_season(player, U256::from(10), U256::from(20 + i), OUTCOME_WON);
+        }
+        let leaderboard = contract.get_leaderboard(season);
+        assert_eq!(leaderboard.len(), LEADERBOARD_SIZE);
+        assert_eq!(leaderboard[0].0, Address::with_last_byte(LEADERBOARD_SIZE as u8));
+        assert!(!leaderboard.iter().any(|(player, _, _)| *player == bob));
+
+        // Rolling the season starts a fresh leaderboard and keeps the old standings.
+        assert_eq!(contract.roll_season().unwrap_or_default(), U256::from(1));
+        assert_eq!(contract.current_season(), (U256::from(1), block::timestamp()));
+        assert_eq!(contract.get_season(season), (0, block::timestamp()));
+        assert!(contract.get_leaderboard(U256::from(1)).is_empty());
+        assert_eq!(contract.get_leaderboard(season).len(), LEADERBOARD_SIZE);
+    }
+
+    #[motsu::test]
+    fn pay_event_settlement(contract: StakeEngine) {
+        let aton: Address = ATON_ADDRESS.parse().expect("Should parse valid hex address");
+        let player = msg::sender();
+        contract._set_vault_aton(aton, Address::ZERO);
+
+        // (event, winner, pools, stake on team 1, expected payout, expected commission)
+        let cases = [
+            // Winners share both pools net of the 2% commission: 300 * 980 / 600.
+            (*b"WXHG1234", 1u8, (600u64, 400u64), 300u64, 490u64, 20u64),
+            // One side empty: the commission is waived and the stake is returned.
+            (*b"ABCR3570", 1u8, (600, 0), 300, 300, 0),
+            // A tie refunds the stake without commission.
+            (*b"XGTY8844", 0u8, (600, 400), 300, 300, 0),
+            // A losing position is paid nothing, the commission is still retained.
+            (*b"QWER1111", 2u8, (600, 400), 300, 0, 20),
+        ];
+
+        for (id, winner, (pool_1, pool_2), stake, payout, commission) in cases {
+            let id8 = FixedBytes::<8>::from(id);
+            {
+                let mut event = contract.events.setter(id8);
+                event.status.set(Uint::<8, 1>::from(2u8));
+                event.winner.set(Uint::<8, 1>::from(winner));
+                event.total.setter(0).unwrap().set(U256::from(pool_1));
+                event.total.setter(1).unwrap().set(U256::from(pool_2));
+                let mut position = event.player.setter(player);
+                position.stake.set(Uint::<128, 2>::from(stake));
+                position.team.set(Uint::<8, 1>::from(1u8));
+            }
+            contract.player_events.setter(player).insert(id8);
+
+            let paid_before = contract.collateral_pools.get(aton).total_paid.get();
+            let commission_before = contract.collateral_pools.get(aton).commission.get();
+            // The collateral transfer itself cannot succeed under motsu; the accounting is
+            // settled before it.
+            let _ = contract.pay_event(String::from_utf8(id.to_vec()).unwrap(), player);
+
+            let pool = contract.collateral_pools.get(aton);
+            assert_eq!(pool.total_paid.get() - paid_before, U256::from(payout));
+            assert_eq!(pool.commission.get() - commission_before, U256::from(commission));
+            assert!(contract.events.get(id8).player.get(player).paid.get());
+        }
+    }
 }
@@ -8,9 +8,10 @@ mod test;
 // -- Imports ------------------------------------------------------------------
 use stylus_sdk::prelude::*;
 use stylus_sdk::{
-    alloy_primitives::{Address, U256},
+    alloy_primitives::{uint, Address, U256, U64},
     alloy_sol_types::sol,
-    call::Call,
+    block,
+    call::{transfer_eth, Call},
     contract, evm, msg,
 };
 
@@ -24,6 +25,22 @@ sol_storage! {
         
         /// Total commission in ATON tokens.
         uint256 total_commission_in_aton;
+
+        /// Commission not yet reflected in `accumulated_commission_per_token`, scaled by
+        /// `COMMISSION_PRECISION`. Carried over into the next distribution.
+        uint256 commission_remainder;
+
+        /// Length in seconds of the epoch over which each deposit is streamed (0 = instant).
+        uint64 reward_duration;
+
+        /// ATON commission streamed per second, scaled by `COMMISSION_PRECISION`.
+        uint256 reward_rate;
+
+        /// Timestamp at which the current ATON commission stream ends.
+        uint64 period_finish;
+
+        /// Timestamp up to which the ATON stream is reflected in `accumulated_commission_per_token`.
+        uint64 last_update_time;
         
         /// Mapping of account to the last commission-per-token value.
         mapping(address => uint256) last_commission_per_token;
@@ -33,9 +50,82 @@ sol_storage! {
         
         /// The address of the ATON token contract.
         address aton_address;
+
+        /// The owner address of this contract for administrative functions.
+        address _owner;
+
+        /// Mapping of accounts excluded from commission distribution (e.g. protocol contracts).
+        mapping(address => bool) excluded;
+
+        /// List of excluded accounts, used to compute the effective supply.
+        address[] excluded_accounts;
+
+        /// Mapping of account to its history of commission payouts (claims and transfer payouts).
+        mapping(address => Claim[]) claim_history;
+
+        /// Additional reward assets distributed alongside ATON commission, keyed by token
+        /// address. Native ETH is stored under `Address::ZERO`.
+        mapping(address => RewardAsset) reward_assets;
+
+        /// List of every reward asset that has ever been registered.
+        address[] reward_asset_list;
+
+        /// Mapping of account to commission settled ahead of a transfer made by the vault
+        /// itself, which the ATON contract cannot report back, and not yet paid.
+        mapping(address => uint256) owed_commission;
+    }
+
+    /// Accounting for a single reward asset other than ATON commission.
+    pub struct RewardAsset {
+        /// Whether new deposits of this asset are accepted.
+        bool whitelisted;
+        /// Whether the asset is already present in `reward_asset_list`.
+        bool listed;
+        /// Accumulated reward per ATON, scaled by `COMMISSION_PRECISION`.
+        uint256 accumulated_per_token;
+        /// Rounding remainder carried over into the next distribution.
+        uint256 remainder;
+        /// Total amount of the asset ever deposited.
+        uint256 total_deposited;
+        /// Amount streamed per second, scaled by `COMMISSION_PRECISION`.
+        uint256 reward_rate;
+        /// Timestamp at which the current stream ends.
+        uint64 period_finish;
+        /// Timestamp up to which the stream is reflected in `accumulated_per_token`.
+        uint64 last_update_time;
+        /// Mapping of account to the last reward-per-token value.
+        mapping(address => uint256) last_per_token;
+        /// Mapping of account to rewards settled but not yet claimed.
+        mapping(address => uint256) owed;
+        /// Mapping of account to the total claimed rewards.
+        mapping(address => uint256) claimed;
+    }
+
+    /// A single commission payout, made through `Vault::claim` or on an ATON transfer.
+    pub struct Claim {
+        /// The amount of ATON paid out.
+        uint256 amount;
+        /// The address that received the commission.
+        address to;
+        /// Timestamp of the claim.
+        uint64 timestamp;
     }
 }
 
+/// Fixed-point scale of `accumulated_commission_per_token` (10^36).
+pub const COMMISSION_PRECISION: U256 =
+    uint!(1_000_000_000_000_000_000_000_000_000_000_000_000_U256);
+
+/// Maximum number of excluded accounts. Every distribution reads the balance of each one,
+/// so the list must stay short.
+pub const MAX_EXCLUDED_ACCOUNTS: usize = 16;
+
+/// Default epoch over which deposits are streamed (7 days).
+pub const DEFAULT_REWARD_DURATION: u64 = 7 * 24 * 60 * 60;
+
+/// Longest epoch the owner can set (365 days), so that `now + duration` cannot overflow.
+pub const MAX_REWARD_DURATION: u64 = 365 * 24 * 60 * 60;
+
 // -- External Interface -------------------------------------------------------
 sol_interface! {
     interface IATON {
@@ -51,15 +141,46 @@ sol_interface! {
         /// Returns the total supply of the token.
         function totalSupply() external view returns (uint256);
     }
+
+    interface IERC20 {
+        /// Transfer tokens from `from` to `to`.
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+
+        /// Transfer tokens from `msg.sender` to `to`.
+        function transfer(address to, uint256 amount) external returns (bool);
+    }
 }
 
 // -- Events & Errors ----------------------------------------------------------
 sol! {
     /// Emitted when the commission is accumulated.
     event CommissionAccumulate(uint256 indexed amount, uint256 indexed newAccPerToken, uint256 indexed totalCommission);
-    
+
+    /// Emitted when a holder claims accrued commission through `claim`.
+    event CommissionClaimed(address indexed player, address indexed to, uint256 amount);
+
+    /// Emitted when a reward asset (`address(0)` for ETH) is deposited for distribution.
+    event RewardAccumulate(address indexed asset, uint256 amount, uint256 newAccPerToken);
+
+    /// Emitted when a holder claims a reward asset through `claim_reward`.
+    event RewardClaimed(address indexed asset, address indexed player, address to, uint256 amount);
+
+    /// Emitted when a reward asset is added to or removed from the whitelist.
+    event RewardAssetUpdated(address indexed asset, bool whitelisted);
+
+    /// Emitted when the owner changes the streaming epoch.
+    event RewardDurationUpdated(uint64 duration);
+
+    /// Emitted when an account is added to or removed from the exclusion list.
+    event ExclusionUpdated(address indexed account, bool excluded);
+
     error Zero(address account);
     error UnauthorizedAccount(address account);
+    error AlreadySet(address account);
+    error TransferFailed(address to, uint256 amount);
+    error InvalidAsset(address asset);
+    error TooManyExcluded(uint256 max);
+    error InvalidDuration(uint64 duration);
 }
 
 /// Represents the ways `Vault` methods may fail.
@@ -67,8 +188,19 @@ sol! {
 pub enum VaultError {
     Zero(Zero),
     UnauthorizedAccount(UnauthorizedAccount),
+    AlreadySet(AlreadySet),
+    TransferFailed(TransferFailed),
+    InvalidAsset(InvalidAsset),
+    TooManyExcluded(TooManyExcluded),
+    InvalidDuration(InvalidDuration),
 }
 
+/// Per-asset entry of `Vault::summary`: asset (`address(0)` for ETH), unclaimed, claimed, total deposited.
+pub type AssetSummary = (Address, U256, U256, U256);
+
+/// Return type of `Vault::summary`.
+pub type VaultSummary = (U256, U256, U256, U256, U256, Vec<AssetSummary>);
+
 #[public] // Expose these functions publicly.
 impl Vault {
     /// Returns the address of the ATON token contract 
@@ -92,9 +224,146 @@ impl Vault {
         }
         // Store the provided ATON contract address in state.
         self.aton_address.set(_aton_address);
+        // The initializer becomes the owner, allowed to manage the exclusion list.
+        self._owner.set(msg::sender());
+        self.reward_duration.set(U64::from(DEFAULT_REWARD_DURATION));
         true
     }
 
+    /// Sets the epoch, in seconds, over which future deposits are streamed. Only callable by the owner.
+    /// A duration of `0` distributes deposits instantly. Streams already running keep their
+    /// rate until the next deposit, which re-spreads the leftover over the new duration.
+    /// The duration cannot exceed `MAX_REWARD_DURATION`.
+    pub fn set_reward_duration(&mut self, duration: u64) -> Result<bool, VaultError> {
+        self.only_owner()?;
+
+        if duration > MAX_REWARD_DURATION {
+            return Err(VaultError::InvalidDuration(InvalidDuration { duration }));
+        }
+
+        self.reward_duration.set(U64::from(duration));
+        evm::log(RewardDurationUpdated { duration });
+
+        Ok(true)
+    }
+
+    /// Returns the epoch, in seconds, over which deposits are streamed.
+    pub fn reward_duration(&self) -> u64 {
+        self.reward_duration.get().to()
+    }
+
+    /// Returns the ATON commission streamed per second, scaled by `COMMISSION_PRECISION`.
+    pub fn reward_rate(&self) -> U256 {
+        self.reward_rate.get()
+    }
+
+    /// Returns the timestamp at which the current ATON commission stream ends.
+    pub fn period_finish(&self) -> u64 {
+        self.period_finish.get().to()
+    }
+
+    /// Returns the stream of a reward asset (`address(0)` for ETH) as `(reward_rate, period_finish)`.
+    pub fn reward_stream(&self, asset: Address) -> (U256, u64) {
+        let reward = self.reward_assets.get(asset);
+        (reward.reward_rate.get(), reward.period_finish.get().to())
+    }
+
+    /// Returns the accumulated commission per token including the part streamed since the
+    /// last update, scaled by `COMMISSION_PRECISION`.
+    pub fn commission_per_token(&self) -> U256 {
+        self._current_commission_per_token()
+    }
+
+    /// Returns the ATON commission earned by `player` so far. Alias of `player_commission`.
+    pub fn earned(&self, player: Address) -> U256 {
+        self.player_commission(player)
+    }
+
+    /// Returns the owner of the vault, set during `initialize`.
+    pub fn owner(&self) -> Address {
+        self._owner.get()
+    }
+
+    /// Adds or removes `account` from the commission exclusion list. Only callable by the owner.
+    ///
+    /// Excluded accounts (the StakeEngine escrow, the Vault itself, the ATON contract...) do not
+    /// earn commission and their balances are left out of the effective supply.
+    /// In both directions the account's `last_commission_per_token` is aligned with the global
+    /// accumulator, so it does not retroactively earn commission. When an account is excluded,
+    /// what it had accrued but not claimed is handed back to the distribution instead of being
+    /// stranded in the vault. At most `MAX_EXCLUDED_ACCOUNTS` accounts can be excluded.
+    ///
+    /// # Arguments
+    /// * `account` - The address to update.
+    /// * `excluded` - `true` to exclude the account, `false` to include it again.
+    ///
+    /// # Returns
+    /// * `Ok(true)` on success.
+    /// * `Err(VaultError::AlreadySet)` if the account already has the requested status.
+    /// * `Err(VaultError::TooManyExcluded)` if the exclusion list is full.
+    /// * `Err(VaultError::UnauthorizedAccount)` if the caller is not the owner.
+    pub fn set_excluded(&mut self, account: Address, excluded: bool) -> Result<bool, VaultError> {
+        self.only_owner()?;
+
+        if self.excluded.get(account) == excluded {
+            return Err(VaultError::AlreadySet(AlreadySet { account }));
+        }
+
+        if excluded && self.excluded_accounts.len() >= MAX_EXCLUDED_ACCOUNTS {
+            return Err(VaultError::TooManyExcluded(TooManyExcluded {
+                max: U256::from(MAX_EXCLUDED_ACCOUNTS),
+            }));
+        }
+
+        // Streams must be brought up to date before the effective supply changes.
+        self._update_rewards()?;
+
+        if excluded {
+            let balance = self._balance_of(account);
+            self._redistribute_pending(account, balance);
+            self.excluded_accounts.push(account);
+        } else {
+            self._remove_excluded_account(account);
+        }
+        self.excluded.setter(account).set(excluded);
+
+        self.last_commission_per_token
+            .setter(account)
+            .set(self.accumulated_commission_per_token.get());
+        self._checkpoint_rewards(account, None);
+
+        evm::log(ExclusionUpdated { account, excluded });
+
+        Ok(true)
+    }
+
+    /// Returns `true` if `account` is excluded from commission distribution.
+    pub fn is_excluded(&self, account: Address) -> bool {
+        self.excluded.get(account)
+    }
+
+    /// Returns every excluded account together with its current ATON balance.
+    pub fn excluded_balances(&self) -> Vec<(Address, U256)> {
+        let mut result = Vec::with_capacity(self.excluded_accounts.len());
+        for i in 0..self.excluded_accounts.len() {
+            if let Some(account) = self.excluded_accounts.get(i) {
+                result.push((account, self._balance_of(account)));
+            }
+        }
+        result
+    }
+
+    /// Returns the sum of the ATON balances held by excluded accounts.
+    pub fn excluded_supply(&self) -> U256 {
+        self._excluded_supply()
+    }
+
+    /// Returns the ATON supply that takes part in commission distribution:
+    /// the total supply minus the balances of every excluded account.
+    pub fn effective_supply(&self) -> Result<U256, VaultError> {
+        self._effective_supply()
+    }
+
     /// Allows the contract to receive ATON tokens from a user and accumulates a global commission.
     /// Logs the `CommissionAccumulate` event on success.
     ///
@@ -111,6 +380,12 @@ impl Vault {
             }));
         }
 
+        // The ATON contract cannot call back into the vault during `transferFrom`:
+        // settle the sender at their balance before the transfer.
+        self._update_rewards()?;
+        let balance = self._balance_of(msg::sender());
+        self._settle_commission(msg::sender(), balance);
+
         // Instantiate an interface to the ATON contract,
         // and configure an internal call context for `transferFrom`.
         let aton_contract = IATON::new(self.aton_address.get());
@@ -138,6 +413,254 @@ impl Vault {
         Ok(true)
     }
 
+    /// Withdraws the caller's accrued commission to `to` without moving any of their ATON.
+    ///
+    /// The caller's `last_commission_per_token` is aligned with the global accumulator,
+    /// `claimed_commissions` is incremented and the claim is appended to the caller's
+    /// claim history before the ATON leaves the vault. The ATON contract cannot call back
+    /// into the vault during the transfer, so `to` is settled here first: what it accrued
+    /// on its current balance moves to `owed_commission` and its checkpoint is aligned.
+    /// Logs the `CommissionClaimed` event on success.
+    ///
+    /// # Arguments
+    /// * `to` - The address receiving the commission.
+    ///
+    /// # Returns
+    /// * `Ok(amount)` with the amount of ATON claimed.
+    /// * `Err(VaultError::Zero)` if `to` is the zero address or there is nothing to claim.
+    /// * `Err(VaultError::TransferFailed)` if the ATON transfer fails.
+    pub fn claim(&mut self, to: Address) -> Result<U256, VaultError> {
+        let player = msg::sender();
+
+        if to == Address::ZERO {
+            return Err(VaultError::Zero(Zero { account: to }));
+        }
+
+        self._update_rewards()?;
+
+        let amount = self.player_commission(player);
+        if amount == U256::ZERO {
+            return Err(VaultError::Zero(Zero { account: player }));
+        }
+
+        // Settle the player's position and the recipient's before the external transfer.
+        self.last_commission_per_token
+            .setter(player)
+            .set(self.accumulated_commission_per_token.get());
+        self.owed_commission.setter(player).set(U256::ZERO);
+        if to != player {
+            let balance = self._balance_of(to);
+            self._settle_commission(to, balance);
+        }
+        self._record_claim(player, to, amount);
+
+        // Pay the commission out of the vault's ATON balance.
+        let aton_contract = IATON::new(self.aton_address.get());
+        let config = Call::new_in(self);
+        aton_contract
+            .transfer(config, to, amount)
+            .map_err(|_| VaultError::TransferFailed(TransferFailed { to, amount }))?;
+
+        evm::log(CommissionClaimed { player, to, amount });
+
+        Ok(amount)
+    }
+
+    /// Returns the number of commission payouts recorded for `player`.
+    pub fn claim_count(&self, player: Address) -> u64 {
+        self.claim_history.getter(player).len() as u64
+    }
+
+    /// Returns a paginated list of `player`'s commission claims, newest first.
+    ///
+    /// # Arguments
+    /// * `player` - The address whose claims are listed.
+    /// * `page_size` - The maximum number of claims to retrieve in one batch.
+    /// * `page` - The page index, used to compute the slice of claims to return.
+    ///
+    /// # Returns
+    /// * A `Vec` of tuples, each containing:
+    ///   1. The claimed amount (`U256`).
+    ///   2. The recipient (`Address`).
+    ///   3. The claim timestamp (`u64`).
+    ///
+    /// If `page_size` or the number of claims is 0, or if the page is out of range, returns an empty list.
+    pub fn get_claim_history(
+        &self,
+        player: Address,
+        page_size: u64,
+        page: u64,
+    ) -> Vec<(U256, Address, u64)> {
+        let history = self.claim_history.getter(player);
+        let length = history.len() as u64;
+        if page_size == 0 || length == 0 {
+            return Vec::new();
+        }
+
+        let end_index = length.saturating_sub(page.saturating_mul(page_size));
+        let start_index = end_index.saturating_sub(page_size);
+
+        let mut claims = Vec::new();
+        for i in (start_index..end_index).rev() {
+            if let Some(entry) = history.get(i as usize) {
+                claims.push((entry.amount.get(), entry.to.get(), entry.timestamp.get().to()));
+            }
+        }
+
+        claims
+    }
+
+    /// Adds or removes an ERC20 `asset` from the reward whitelist. Only callable by the owner.
+    /// Native ETH is always accepted and ATON is distributed through `accumulate_aton`,
+    /// so neither can be whitelisted here. Removing an asset stops new deposits but
+    /// never blocks claims of what was already distributed.
+    ///
+    /// # Returns
+    /// * `Ok(true)` on success.
+    /// * `Err(VaultError::InvalidAsset)` if `asset` is `address(0)` or the ATON contract.
+    /// * `Err(VaultError::UnauthorizedAccount)` if the caller is not the owner.
+    pub fn set_reward_asset(&mut self, asset: Address, whitelisted: bool) -> Result<bool, VaultError> {
+        self.only_owner()?;
+
+        if asset == Address::ZERO || asset == self.aton_address.get() {
+            return Err(VaultError::InvalidAsset(InvalidAsset { asset }));
+        }
+
+        self._list_reward_asset(asset);
+        self.reward_assets.setter(asset).whitelisted.set(whitelisted);
+
+        evm::log(RewardAssetUpdated { asset, whitelisted });
+
+        Ok(true)
+    }
+
+    /// Returns every registered reward asset (`address(0)` for ETH).
+    pub fn reward_assets(&self) -> Vec<Address> {
+        let mut assets = Vec::with_capacity(self.reward_asset_list.len());
+        for i in 0..self.reward_asset_list.len() {
+            if let Some(asset) = self.reward_asset_list.get(i) {
+                assets.push(asset);
+            }
+        }
+        assets
+    }
+
+    /// Returns `true` if deposits of `asset` are accepted.
+    pub fn is_reward_asset(&self, asset: Address) -> bool {
+        asset == Address::ZERO || self.reward_assets.get(asset).whitelisted.get()
+    }
+
+    /// Distributes the attached ETH (e.g. swap fees) to ATON holders.
+    ///
+    /// # Returns
+    /// * `Ok(true)` on success.
+    /// * `Err(VaultError::Zero)` if no ETH is attached.
+    #[payable]
+    pub fn accumulate_eth(&mut self) -> Result<bool, VaultError> {
+        let amount = msg::value();
+        if amount == U256::ZERO {
+            return Err(VaultError::Zero(Zero {
+                account: msg::sender(),
+            }));
+        }
+
+        self._list_reward_asset(Address::ZERO);
+        self._add_reward(Address::ZERO, amount)?;
+
+        Ok(true)
+    }
+
+    /// Pulls `amount` of the whitelisted ERC20 `asset` from the caller and distributes it to ATON holders.
+    ///
+    /// # Returns
+    /// * `Ok(true)` on success.
+    /// * `Err(VaultError::Zero)` if `amount` is zero.
+    /// * `Err(VaultError::InvalidAsset)` if `asset` is not whitelisted.
+    /// * `Err(VaultError::TransferFailed)` if the `transferFrom` call fails.
+    pub fn accumulate_reward(&mut self, asset: Address, amount: U256) -> Result<bool, VaultError> {
+        if amount == U256::ZERO {
+            return Err(VaultError::Zero(Zero {
+                account: msg::sender(),
+            }));
+        }
+        if asset == Address::ZERO || !self.reward_assets.get(asset).whitelisted.get() {
+            return Err(VaultError::InvalidAsset(InvalidAsset { asset }));
+        }
+
+        let token = IERC20::new(asset);
+        let config = Call::new_in(self);
+        token
+            .transfer_from(config, msg::sender(), contract::address(), amount)
+            .map_err(|_| {
+                VaultError::TransferFailed(TransferFailed {
+                    to: contract::address(),
+                    amount,
+                })
+            })?;
+
+        self._add_reward(asset, amount)?;
+
+        Ok(true)
+    }
+
+    /// Returns the unclaimed amount of `asset` (`address(0)` for ETH) owed to `player`.
+    pub fn pending_reward(&self, asset: Address, player: Address) -> U256 {
+        if self.excluded.get(player) {
+            return self.reward_assets.get(asset).owed.get(player);
+        }
+        self._pending_reward(asset, player, self._balance_of(player))
+    }
+
+    /// Withdraws the caller's accrued `asset` rewards (`address(0)` for ETH) to `to`.
+    /// Logs the `RewardClaimed` event on success.
+    ///
+    /// # Returns
+    /// * `Ok(amount)` with the amount claimed.
+    /// * `Err(VaultError::Zero)` if `to` is the zero address or there is nothing to claim.
+    /// * `Err(VaultError::TransferFailed)` if the payout fails.
+    pub fn claim_reward(&mut self, asset: Address, to: Address) -> Result<U256, VaultError> {
+        let player = msg::sender();
+
+        if to == Address::ZERO {
+            return Err(VaultError::Zero(Zero { account: to }));
+        }
+
+        self._update_rewards()?;
+
+        let amount = self.pending_reward(asset, player);
+        if amount == U256::ZERO {
+            return Err(VaultError::Zero(Zero { account: player }));
+        }
+
+        // Settle the player's position before the external transfer.
+        let mut reward = self.reward_assets.setter(asset);
+        let accumulated = reward.accumulated_per_token.get();
+        reward.last_per_token.setter(player).set(accumulated);
+        reward.owed.setter(player).set(U256::ZERO);
+        let claimed = reward.claimed.get(player);
+        reward.claimed.setter(player).set(claimed + amount);
+
+        if asset == Address::ZERO {
+            transfer_eth(to, amount)
+                .map_err(|_| VaultError::TransferFailed(TransferFailed { to, amount }))?;
+        } else {
+            let token = IERC20::new(asset);
+            let config = Call::new_in(self);
+            token
+                .transfer(config, to, amount)
+                .map_err(|_| VaultError::TransferFailed(TransferFailed { to, amount }))?;
+        }
+
+        evm::log(RewardClaimed {
+            asset,
+            player,
+            to,
+            amount,
+        });
+
+        Ok(amount)
+    }
+
     /// Provides a summary of a player's relevant balances and commission data.
     ///
     /// # Returns
@@ -146,24 +669,48 @@ impl Vault {
     /// 2) The player's ATON balance,
     /// 3) The player's unclaimed commission,
     /// 4) The player's claimed commissions (from storage),
-    /// 5) The total commission in ATON currently tracked by the vault.
-    pub fn summary(&self, player: Address) -> Result<(U256, U256, U256, U256, U256), VaultError> {
+    /// 5) The total commission in ATON currently tracked by the vault,
+    /// 6) For every other reward asset: the asset (`address(0)` for ETH), the player's
+    ///    unclaimed and claimed amounts, and the total deposited.
+    pub fn summary(&self, player: Address) -> Result<VaultSummary, VaultError> {
         let aton_balance = self._balance_of(player);
+
+        let mut assets = Vec::with_capacity(self.reward_asset_list.len());
+        for i in 0..self.reward_asset_list.len() {
+            if let Some(asset) = self.reward_asset_list.get(i) {
+                let reward = self.reward_assets.get(asset);
+                assets.push((
+                    asset,
+                    self.pending_reward(asset, player),
+                    reward.claimed.get(player),
+                    reward.total_deposited.get(),
+                ));
+            }
+        }
+
         Ok((
             player.balance(),
             aton_balance,
             self.player_commission(player),
             self.claimed_commissions.get(player),
             *self.total_commission_in_aton,
+            assets,
         ))
     }
 
+    /// Returns the commission deposited but not yet distributed because of rounding,
+    /// in ATON (truncated). It is carried over into the next distribution.
+    pub fn undistributed_commission(&self) -> U256 {
+        self.commission_remainder.get() / COMMISSION_PRECISION
+    }
+
     /// Calculates the unclaimed commission for a given player.
-    /// 
-    /// The formula: 
+    ///
+    /// The formula:
     /// (accumulated_commission_per_token - last_commission_per_token_for_player) * player_aton_balance
-    /// 
-    /// Then it scales down the result to factor in `10^18` decimals, returning the net unclaimed commission.
+    ///
+    /// Then it scales down the result by `COMMISSION_PRECISION` and adds the commission already
+    /// settled into `owed_commission`, returning the net unclaimed commission.
     ///
     /// # Arguments
     /// * `player` - The address of the player whose commission is being computed.
@@ -171,32 +718,22 @@ impl Vault {
     /// # Returns
     /// The player's unclaimed commission (in ATON) as a `U256`.
     pub fn player_commission(&self, player: Address) -> U256 {
+        // Excluded accounts never accrue commission.
+        let owed = self.owed_commission.get(player);
+        if self.excluded.get(player) {
+            return owed;
+        }
+
         // 1) Calculate the difference in commission-per-token since the player's last update.
         let owed_per_token = self
-            .accumulated_commission_per_token
+            ._current_commission_per_token()
             .saturating_sub(self.last_commission_per_token.get(player));
 
         // 2) Retrieve player's ATON balance.
         let player_aton_balance = self._balance_of(player);
 
-        // Setup scales: decimals = 10^18, pct_denom = 10^7 for further subdivision if needed.
-        let decimals = U256::from(10).pow(U256::from(18));
-        let pct_denom = U256::from(10000000u64);
-
-        // 3) Multiply and scale.
-        let scaled = player_aton_balance
-            .checked_mul(owed_per_token)
-            .unwrap_or(U256::ZERO)
-            .checked_mul(pct_denom)
-            .unwrap_or(U256::ZERO)
-            / decimals;
-
-        // 4) Return the final scaled value or zero if scaled is zero.
-        if scaled > U256::ZERO {
-            scaled / pct_denom
-        } else {
-            U256::ZERO
-        }
+        // 3) Multiply, scale down and add what was settled earlier.
+        owed + Vault::_commission_for(player_aton_balance, owed_per_token)
     }
 
     /// Resets a player's unclaimed commission to zero by aligning their `last_commission_per_token`
@@ -204,8 +741,10 @@ impl Vault {
     /// the ATON contract (verified by `msg::sender()`).
     ///
     /// Typically called internally when a player's ATON tokens are transferred via the overridden
-    /// transfer logic in the ATON contract.
-    pub fn clear_commission(&mut self, player: Address) -> Result<(), VaultError> {
+    /// transfer logic in the ATON contract, which pays the accrued commission to the player
+    /// first. That payout (`paid`) is added to `claimed_commissions` and the claim history
+    /// like an explicit `claim`.
+    pub fn clear_commission(&mut self, player: Address, paid: U256) -> Result<(), VaultError> {
         // Only the ATON contract can call this function.
         if msg::sender() != self.aton_address.get() {
             return Err(VaultError::UnauthorizedAccount(UnauthorizedAccount {
@@ -213,10 +752,29 @@ impl Vault {
             }));
         }
 
+        // Bring the streams up to date with the balances as they were before the transfer.
+        self._update_rewards()?;
+
         // Align this player's `last_commission_per_token` with the global accumulated_commission_per_token.
         self.last_commission_per_token
             .setter(player)
             .set(self.accumulated_commission_per_token.get());
+        self.owed_commission.setter(player).set(U256::ZERO);
+
+        // Other reward assets are not paid on transfer: settle them at the pre-transfer balance.
+        if !self.excluded.get(player) {
+            let balance = self._balance_of(player);
+            self._checkpoint_rewards(player, Some(balance));
+        }
+
+        if paid > U256::ZERO {
+            self._record_claim(player, player, paid);
+            evm::log(CommissionClaimed {
+                player,
+                to: player,
+                amount: paid,
+            });
+        }
 
         Ok(())
     }
@@ -225,12 +783,14 @@ impl Vault {
 // -- Private Functions --------------------------------------------------------
 impl Vault {
     /// Updates the global commission counters when new ATON is deposited.
-    /// 
-    /// It calculates how much commission is added per token by dividing 
-    /// `new_commission_aton` by the total supply of ATON tokens, scaled by `10^18`.
-    /// 
-    /// Then, it updates the stored `accumulated_commission_per_token` and
-    /// `total_commission_in_aton`.
+    ///
+    /// Streams are first brought up to date. With a `reward_duration` of zero the deposit
+    /// (plus the remainder carried from previous deposits) is divided instantly by the
+    /// effective supply of ATON tokens (total supply minus excluded balances), scaled by
+    /// `COMMISSION_PRECISION`. Otherwise the deposit and whatever is left of the running
+    /// stream are spread linearly over a new epoch of `reward_duration` seconds.
+    ///
+    /// Then, it updates `total_commission_in_aton`.
     ///
     /// # Arguments
     /// * `new_commission_aton` - The amount of newly deposited ATON.
@@ -238,32 +798,430 @@ impl Vault {
     /// # Returns
     /// `Ok(())` if commission added successfully, or an error variant if the call fails.
     pub fn _add_commission(&mut self, new_commission_aton: U256) -> Result<(), VaultError> {
-        // Get a handle to the ATON contract interface.
+        self._add_commission_at(new_commission_aton, block::timestamp(), &mut None)
+    }
+
+    /// `_add_commission` at timestamp `now`, reading the effective supply through `supply`.
+    pub fn _add_commission_at(
+        &mut self,
+        new_commission_aton: U256,
+        now: u64,
+        supply: &mut Option<U256>,
+    ) -> Result<(), VaultError> {
+        self._update_rewards_at(now, supply)?;
+
+        let duration: u64 = self.reward_duration.get().to();
+        if duration == 0 {
+            // Retrieve the effective supply of ATON to compute the new commission rate.
+            let total_supply_tokens = self._cached_supply(supply)?;
+
+            // Split the deposit (and any carried remainder) into a per-token increment and
+            // a new remainder. With a zero supply everything is carried over.
+            let (additional_commission, remainder) = Vault::_split_commission(
+                new_commission_aton,
+                self.commission_remainder.get(),
+                total_supply_tokens,
+            );
+
+            // Update the global `accumulated_commission_per_token`.
+            self.accumulated_commission_per_token
+                .set(self.accumulated_commission_per_token.get() + additional_commission);
+            self.commission_remainder.set(remainder);
+        } else {
+            // Re-spread the deposit and the leftover of the running stream over a new epoch.
+            let remaining = self.period_finish.get().to::<u64>().saturating_sub(now);
+            let (rate, dust) = Vault::_stream_rate(
+                new_commission_aton,
+                self.reward_rate.get(),
+                remaining,
+                duration,
+            );
+
+            self.reward_rate.set(rate);
+            self.commission_remainder
+                .set(self.commission_remainder.get() + dust);
+            self.last_update_time.set(U64::from(now));
+            self.period_finish.set(U64::from(now + duration));
+        }
+
+        // Update the total ATON commission stored.
+        self.total_commission_in_aton
+            .set(self.total_commission_in_aton.get() + new_commission_aton);
+
+        Ok(())
+    }
+
+    /// Brings the ATON commission stream and every reward asset stream up to the current
+    /// block, moving what was streamed since the last update into the accumulators.
+    /// The effective supply is only fetched when a stream actually has something to add.
+    pub fn _update_rewards(&mut self) -> Result<(), VaultError> {
+        self._update_rewards_at(block::timestamp(), &mut None)
+    }
+
+    /// `_update_rewards` at timestamp `now`, reading the effective supply through `supply`.
+    pub fn _update_rewards_at(&mut self, now: u64, supply: &mut Option<U256>) -> Result<(), VaultError> {
+
+        let last: u64 = self.last_update_time.get().to();
+        let end = Vault::_last_time_applicable(now, self.period_finish.get().to());
+        if end > last {
+            let rate = self.reward_rate.get();
+            if rate > U256::ZERO {
+                let total_supply_tokens = self._cached_supply(supply)?;
+                let (additional_commission, remainder) = Vault::_split_scaled(
+                    rate * U256::from(end - last),
+                    self.commission_remainder.get(),
+                    total_supply_tokens,
+                );
+                self.accumulated_commission_per_token
+                    .set(self.accumulated_commission_per_token.get() + additional_commission);
+                self.commission_remainder.set(remainder);
+            }
+            self.last_update_time.set(U64::from(end));
+        }
+
+        for i in 0..self.reward_asset_list.len() {
+            let Some(asset) = self.reward_asset_list.get(i) else {
+                continue;
+            };
+
+            let reward = self.reward_assets.get(asset);
+            let last: u64 = reward.last_update_time.get().to();
+            let end = Vault::_last_time_applicable(now, reward.period_finish.get().to());
+            if end <= last {
+                continue;
+            }
+            let rate = reward.reward_rate.get();
+            let remainder = reward.remainder.get();
+
+            let split = if rate > U256::ZERO {
+                let total_supply_tokens = self._cached_supply(supply)?;
+                Some(Vault::_split_scaled(
+                    rate * U256::from(end - last),
+                    remainder,
+                    total_supply_tokens,
+                ))
+            } else {
+                None
+            };
+
+            let mut reward = self.reward_assets.setter(asset);
+            if let Some((additional, remainder)) = split {
+                let accumulated = reward.accumulated_per_token.get() + additional;
+                reward.accumulated_per_token.set(accumulated);
+                reward.remainder.set(remainder);
+            }
+            reward.last_update_time.set(U64::from(end));
+        }
+
+        Ok(())
+    }
+
+    /// Returns `accumulated_commission_per_token` plus what was streamed since the last
+    /// update, without writing to storage. Falls back to the stored value if the effective
+    /// supply cannot be read.
+    pub fn _current_commission_per_token(&self) -> U256 {
+        let stored = self.accumulated_commission_per_token.get();
+        let end = Vault::_last_time_applicable(block::timestamp(), self.period_finish.get().to());
+        let elapsed = end.saturating_sub(self.last_update_time.get().to());
+        let rate = self.reward_rate.get();
+        if elapsed == 0 || rate == U256::ZERO {
+            return stored;
+        }
+
+        match self._effective_supply() {
+            Ok(supply) => {
+                let (additional, _) = Vault::_split_scaled(
+                    rate * U256::from(elapsed),
+                    self.commission_remainder.get(),
+                    supply,
+                );
+                stored + additional
+            }
+            Err(_) => stored,
+        }
+    }
+
+    /// Returns the accumulated reward per token of `asset` plus what was streamed since the
+    /// last update, without writing to storage.
+    pub fn _current_reward_per_token(&self, asset: Address) -> U256 {
+        let reward = self.reward_assets.get(asset);
+        let stored = reward.accumulated_per_token.get();
+        let end = Vault::_last_time_applicable(block::timestamp(), reward.period_finish.get().to());
+        let elapsed = end.saturating_sub(reward.last_update_time.get().to());
+        let rate = reward.reward_rate.get();
+        if elapsed == 0 || rate == U256::ZERO {
+            return stored;
+        }
+
+        match self._effective_supply() {
+            Ok(supply) => {
+                let (additional, _) = Vault::_split_scaled(
+                    rate * U256::from(elapsed),
+                    reward.remainder.get(),
+                    supply,
+                );
+                stored + additional
+            }
+            Err(_) => stored,
+        }
+    }
+
+    /// Returns the effective supply, reading it at most once per call of `_update_rewards`.
+    pub fn _cached_supply(&self, cache: &mut Option<U256>) -> Result<U256, VaultError> {
+        if let Some(supply) = *cache {
+            return Ok(supply);
+        }
+        let supply = self._effective_supply()?;
+        *cache = Some(supply);
+        Ok(supply)
+    }
+
+    /// Splits a deposit into the increment of `accumulated_commission_per_token` and the
+    /// rounding remainder to carry over.
+    ///
+    /// `amount * COMMISSION_PRECISION + remainder` is divided by `supply`; the quotient is
+    /// the per-token increment and the modulo the new remainder, so that
+    /// `increment * supply + new_remainder == amount * COMMISSION_PRECISION + remainder`.
+    ///
+    /// # Arguments
+    /// * `amount` - The amount of newly deposited ATON.
+    /// * `remainder` - The remainder carried from previous deposits, scaled by `COMMISSION_PRECISION`.
+    /// * `supply` - The effective supply the deposit is distributed over.
+    ///
+    /// # Returns
+    /// A tuple of `(per_token_increment, new_remainder)`.
+    pub fn _split_commission(amount: U256, remainder: U256, supply: U256) -> (U256, U256) {
+        Vault::_split_scaled(amount * COMMISSION_PRECISION, remainder, supply)
+    }
+
+    /// Same as `_split_commission` for an amount already scaled by `COMMISSION_PRECISION`.
+    pub fn _split_scaled(scaled: U256, remainder: U256, supply: U256) -> (U256, U256) {
+        let scaled = scaled + remainder;
+        if supply == U256::ZERO {
+            return (U256::ZERO, scaled);
+        }
+        (scaled / supply, scaled % supply)
+    }
+
+    /// Computes the rate of a new stream of `amount` plus the leftover of the running one
+    /// (`rate * remaining`) spread over `duration` seconds.
+    ///
+    /// # Returns
+    /// A tuple of `(new_rate, dust)`, both scaled by `COMMISSION_PRECISION`, where `dust`
+    /// is the rounding remainder of the division by `duration`, so that
+    /// `new_rate * duration + dust == amount * COMMISSION_PRECISION + rate * remaining`.
+    pub fn _stream_rate(amount: U256, rate: U256, remaining: u64, duration: u64) -> (U256, U256) {
+        let scaled = amount * COMMISSION_PRECISION + rate * U256::from(remaining);
+        let duration = U256::from(duration);
+        (scaled / duration, scaled % duration)
+    }
+
+    /// Returns the last timestamp at which a stream ending at `period_finish` still pays out.
+    pub fn _last_time_applicable(now: u64, period_finish: u64) -> u64 {
+        now.min(period_finish)
+    }
+
+    /// Converts a per-token commission delta into ATON owed for `balance`.
+    pub fn _commission_for(balance: U256, owed_per_token: U256) -> U256 {
+        balance
+            .checked_mul(owed_per_token)
+            .map(|scaled| scaled / COMMISSION_PRECISION)
+            .unwrap_or(U256::ZERO)
+    }
+
+    /// Distributes `amount` of `asset` over the effective ATON supply, instantly or as a
+    /// stream depending on `reward_duration` like `_add_commission`, and logs `RewardAccumulate`.
+    pub fn _add_reward(&mut self, asset: Address, amount: U256) -> Result<(), VaultError> {
+        self._update_rewards()?;
+
+        let duration: u64 = self.reward_duration.get().to();
+        let instant_supply = if duration == 0 {
+            Some(self._effective_supply()?)
+        } else {
+            None
+        };
+        let now = block::timestamp();
+
+        let mut reward = self.reward_assets.setter(asset);
+        if let Some(total_supply_tokens) = instant_supply {
+            let (additional, remainder) =
+                Vault::_split_commission(amount, reward.remainder.get(), total_supply_tokens);
+            let accumulated = reward.accumulated_per_token.get() + additional;
+            reward.accumulated_per_token.set(accumulated);
+            reward.remainder.set(remainder);
+        } else {
+            let remaining = reward.period_finish.get().to::<u64>().saturating_sub(now);
+            let (rate, dust) =
+                Vault::_stream_rate(amount, reward.reward_rate.get(), remaining, duration);
+            reward.reward_rate.set(rate);
+            let remainder = reward.remainder.get();
+            reward.remainder.set(remainder + dust);
+            reward.last_update_time.set(U64::from(now));
+            reward.period_finish.set(U64::from(now + duration));
+        }
+        let total_deposited = reward.total_deposited.get();
+        reward.total_deposited.set(total_deposited + amount);
+
+        evm::log(RewardAccumulate {
+            asset,
+            amount,
+            newAccPerToken: reward.accumulated_per_token.get(),
+        });
+
+        Ok(())
+    }
+
+    /// Returns the `asset` rewards owed to `player` for the given ATON `balance`,
+    /// including rewards already settled into `owed`.
+    pub fn _pending_reward(&self, asset: Address, player: Address, balance: U256) -> U256 {
+        let reward = self.reward_assets.get(asset);
+        let owed_per_token = self
+            ._current_reward_per_token(asset)
+            .saturating_sub(reward.last_per_token.get(player));
+
+        reward.owed.get(player) + Vault::_commission_for(balance, owed_per_token)
+    }
+
+    /// Aligns `player`'s checkpoint with the accumulator of every reward asset.
+    /// With `Some(balance)` the rewards earned since the last checkpoint are settled into
+    /// `owed` first; with `None` they are forfeited (used when the exclusion status changes).
+    pub fn _checkpoint_rewards(&mut self, player: Address, balance: Option<U256>) {
+        for i in 0..self.reward_asset_list.len() {
+            let Some(asset) = self.reward_asset_list.get(i) else {
+                continue;
+            };
+
+            if let Some(balance) = balance {
+                let pending = self._pending_reward(asset, player, balance);
+                self.reward_assets.setter(asset).owed.setter(player).set(pending);
+            }
+
+            let mut reward = self.reward_assets.setter(asset);
+            let accumulated = reward.accumulated_per_token.get();
+            reward.last_per_token.setter(player).set(accumulated);
+        }
+    }
+
+    /// Moves the commission `account` accrued on `balance` since its last checkpoint into
+    /// `owed_commission` and aligns the checkpoint with the accumulator. Used before the vault
+    /// moves ATON in or out of an account itself.
+    pub fn _settle_commission(&mut self, account: Address, balance: U256) {
+        let accumulated = self.accumulated_commission_per_token.get();
+        if !self.excluded.get(account) {
+            let owed_per_token = accumulated.saturating_sub(self.last_commission_per_token.get(account));
+            let owed = self.owed_commission.get(account);
+            self.owed_commission
+                .setter(account)
+                .set(owed + Vault::_commission_for(balance, owed_per_token));
+        }
+        self.last_commission_per_token.setter(account).set(accumulated);
+    }
+
+    /// Hands the commission and rewards `account` accrued on `balance` since its last
+    /// checkpoint back to the distribution, through the rounding remainders carried into the
+    /// next one. Used when the account is excluded; rewards already settled into `owed` stay
+    /// claimable.
+    pub fn _redistribute_pending(&mut self, account: Address, balance: U256) {
+        let owed_per_token = self
+            .accumulated_commission_per_token
+            .get()
+            .saturating_sub(self.last_commission_per_token.get(account));
+        let pending = balance.saturating_mul(owed_per_token);
+        self.commission_remainder
+            .set(self.commission_remainder.get() + pending);
+
+        for i in 0..self.reward_asset_list.len() {
+            let Some(asset) = self.reward_asset_list.get(i) else {
+                continue;
+            };
+
+            let mut reward = self.reward_assets.setter(asset);
+            let owed_per_token = reward
+                .accumulated_per_token
+                .get()
+                .saturating_sub(reward.last_per_token.get(account));
+            let remainder = reward.remainder.get();
+            reward
+                .remainder
+                .set(remainder + balance.saturating_mul(owed_per_token));
+        }
+    }
+
+    /// Adds a commission payout of `player` to `claimed_commissions` and their claim history.
+    pub fn _record_claim(&mut self, player: Address, to: Address, amount: U256) {
+        let claimed = self.claimed_commissions.get(player);
+        self.claimed_commissions.setter(player).set(claimed + amount);
+
+        let mut history = self.claim_history.setter(player);
+        let mut entry = history.grow();
+        entry.amount.set(amount);
+        entry.to.set(to);
+        entry.timestamp.set(U64::from(block::timestamp()));
+    }
+
+    /// Appends `asset` to `reward_asset_list` the first time it is seen.
+    pub fn _list_reward_asset(&mut self, asset: Address) {
+        if self.reward_assets.get(asset).listed.get() {
+            return;
+        }
+        self.reward_assets.setter(asset).listed.set(true);
+        self.reward_asset_list.push(asset);
+    }
+
+    /// Returns the total supply of ATON minus the balances held by excluded accounts.
+    ///
+    /// # Returns
+    /// The effective supply, or `VaultError::Zero` if the `totalSupply` call fails.
+    pub fn _effective_supply(&self) -> Result<U256, VaultError> {
         let aton_contract = IATON::new(self.aton_address.get());
-        let config = Call::new_in(self);
 
-        // Retrieve the total supply of ATON to compute the new commission rate.
-        let total_supply_tokens = aton_contract.total_supply(config).map_err(|_| {
+        let total_supply = aton_contract.total_supply(self).map_err(|_| {
             VaultError::Zero(Zero {
                 account: msg::sender(),
             })
         })?;
 
-        // Avoid division by zero if the supply is somehow zero.
-        if total_supply_tokens > U256::ZERO {
-            // Commission per token scaled by 10^18 to maintain precision.
-            let additional_commission =
-                (new_commission_aton * U256::from(10).pow(U256::from(18u8))) / total_supply_tokens;
+        Ok(total_supply.saturating_sub(self._excluded_supply()))
+    }
 
-            // Update the global `accumulated_commission_per_token`.
-            self.accumulated_commission_per_token
-                .set(self.accumulated_commission_per_token.get() + additional_commission);
+    /// Sums the ATON balances of every account in `excluded_accounts`.
+    pub fn _excluded_supply(&self) -> U256 {
+        let mut total = U256::ZERO;
+        for i in 0..self.excluded_accounts.len() {
+            if let Some(account) = self.excluded_accounts.get(i) {
+                total += self._balance_of(account);
+            }
+        }
+        total
+    }
 
-            // Update the total ATON commission stored.
-            self.total_commission_in_aton
-                .set(self.total_commission_in_aton.get() + new_commission_aton);
+    /// Removes `account` from `excluded_accounts` by swapping it with the last element and popping.
+    /// The scan is bounded by `MAX_EXCLUDED_ACCOUNTS`.
+    pub fn _remove_excluded_account(&mut self, account: Address) {
+        let length = self.excluded_accounts.len();
+        for i in 0..length {
+            if self.excluded_accounts.get(i) == Some(account) {
+                if i < length - 1 {
+                    if let Some(last) = self.excluded_accounts.get(length - 1) {
+                        self.excluded_accounts.setter(i).unwrap().set(last);
+                    }
+                }
+                self.excluded_accounts.pop();
+                return;
+            }
         }
+    }
 
+    /// Checks if the caller (`msg::sender()`) is the owner.
+    ///
+    /// # Returns
+    /// * `Ok(())` if `msg::sender()` matches `_owner`.
+    /// * `Err(VaultError::UnauthorizedAccount(...))` otherwise.
+    pub fn only_owner(&self) -> Result<(), VaultError> {
+        let account = msg::sender();
+        if self._owner.get() != account {
+            return Err(VaultError::UnauthorizedAccount(UnauthorizedAccount { account }));
+        }
         Ok(())
     }
 
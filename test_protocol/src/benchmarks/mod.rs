@@ -0,0 +1,77 @@
+// src/benchmarks/mod.rs
+//
+// Gas benchmarks against a local deployment (run ./local_deploy.sh first).
+
+use crate::call_contract::call_contract_method_signed;
+use crate::constants::env_vars::get_env_vars;
+use crate::constants::wallets::Wallet;
+use crate::core_events;
+use crate::utils::{get_block_time, sleep_ms};
+use ethers::prelude::*;
+use eyre::Result;
+use std::sync::Arc;
+
+/// Gas used to close the newest of `open_events` opened events.
+///
+/// The newest event sits at the end of `opened_events`, which was the worst case of the
+/// former linear scan; with the indexed set the cost should not grow with `open_events`.
+/// Run it with increasing sizes on a build before and after the change to compare.
+pub async fn close_event_gas(open_events: u64, oracle_wallet: &Wallet) -> Result<U256> {
+    let start_date = get_block_time().await? + 5;
+    // Prefix ids with the start date so repeated runs do not collide.
+    let prefix = start_date % 1000;
+    let mut last_event_id = String::new();
+    for i in 0..open_events {
+        last_event_id = format!("B{:03}{:04}", prefix, i);
+        core_events::add_event(&last_event_id, start_date, oracle_wallet).await?;
+    }
+
+    while get_block_time().await? < start_date {
+        sleep_ms(1000);
+    }
+
+    let abi_json = r#"[
+        {
+            "inputs": [
+                { "internalType": "string", "name": "event_id", "type": "string" },
+                { "internalType": "uint8", "name": "winner", "type": "uint8" }
+            ],
+            "name": "closeEvent",
+            "outputs": [
+                { "internalType": "bool", "name": "", "type": "bool" }
+            ],
+            "stateMutability": "nonpayable",
+            "type": "function"
+        }
+    ]"#;
+
+    let env = get_env_vars();
+    let wallet = oracle_wallet
+        .private_key
+        .parse::<LocalWallet>()?
+        .with_chain_id(env.chain_id);
+    let signer = Arc::new(SignerMiddleware::new(
+        Provider::<Http>::try_from(env.rpc_url)?,
+        wallet,
+    ));
+
+    let receipt = call_contract_method_signed(
+        "closeEvent",
+        (last_event_id, 1u8),
+        abi_json,
+        &env.core_address,
+        signer,
+        U256::zero(),
+    )
+    .await?;
+
+    let gas_used = receipt
+        .and_then(|receipt| receipt.gas_used)
+        .unwrap_or_default();
+    println!(
+        "\n[Benchmark] closeEvent with {} opened events: {} gas",
+        open_events, gas_used
+    );
+
+    Ok(gas_used)
+}
@@ -0,0 +1,119 @@
+// src/call_contract/errors.rs
+
+use ethers::abi::ethabi::AbiError;
+use ethers::abi::{HumanReadableParser, Token};
+use ethers::contract::EthError;
+use ethers::utils::hex;
+
+/// Custom errors of the protocol contracts, in human-readable ABI form.
+const KNOWN_ERRORS: &[&str] = &[
+    // StakeEngine
+    "error AlreadyInitialized()",
+    "error UnauthorizedAccount(address account)",
+    "error InvalidAddress(address account)",
+    "error AtonCallFailed(address aton)",
+    "error CollateralNotAllowed(address token)",
+    "error CollateralLocked(bytes8 event_id)",
+    "error EthNotAccepted(address collateral)",
+    "error InvalidTeam(uint8 team)",
+    "error TeamLocked(uint8 team)",
+    "error WrongStatus(bytes8 event_id, uint8 status, uint8 expected)",
+    "error AlreadyStarted(bytes8 event_id, uint64 start_date, uint64 timestamp)",
+    "error EventNotFound(bytes8 event_id)",
+    "error PlayerEventNotFound(bytes8 event_id, address player)",
+    "error MintFailed(uint256 value)",
+    "error TransferFailed(address token, address from, address to, uint256 amount)",
+    "error SlippageExceeded(uint256 multiplier, uint256 min_multiplier)",
+    "error DeadlineExpired(uint64 deadline, uint64 timestamp)",
+    "error SlipCallFailed(uint256 token_id)",
+    "error UnknownSlip(uint256 token_id)",
+    "error SlipTransferred(uint256 token_id, address holder)",
+    "error InvalidLegCount(uint256 count)",
+    "error DuplicateLeg(bytes8 event_id)",
+    "error LegNotPriced(bytes8 event_id, uint8 team)",
+    "error MixedCollateral(bytes8 event_id, address collateral)",
+    "error InsufficientParlayReserve(uint256 available, uint256 needed)",
+    "error ParlayNotOpen(uint256 ticket_id)",
+    "error StakeBelowMinimum(uint256 amount, uint256 min_stake)",
+    "error PlayerStakeExceeded(uint256 stake, uint256 max_player_stake)",
+    "error OutcomePoolExceeded(uint256 pool, uint256 max_outcome_pool)",
+    "error PoolImbalanceExceeded(uint256 imbalance, uint256 max_imbalance)",
+    "error StakeOverflow(uint256 stake)",
+    "error SelfExcluded(address player, uint64 until)",
+    "error SpendLimitExceeded(uint8 period, uint256 spent, uint256 limit)",
+    "error InvalidPeriod(uint8 period)",
+    "error InvalidSignature()",
+    "error NonceUsed(address player, uint256 nonce)",
+    "error ReferrerAlreadySet(address referrer)",
+    "error InvalidReferrer(address referrer)",
+    "error InvalidShare(uint256 share)",
+    "error InvalidMarketKind(uint8 kind)",
+    "error MarketWrongStatus(uint256 market_id, uint8 status)",
+    "error InvalidOutcome(uint32 outcome)",
+    "error OutcomeLocked(uint32 outcome)",
+    "error NothingToClaim(uint256 market_id, address player)",
+    "error InvalidOutcomeCount(uint256 count)",
+    "error OutrightWrongStatus(uint256 outright_id, uint8 status)",
+    "error OutrightClosed(uint256 outright_id, uint64 close_time)",
+    "error OutrightNotClosed(uint256 outright_id, uint64 close_time)",
+    "error CreditCapExceeded(uint256 outstanding, uint256 cap)",
+    "error InsufficientCredit(uint256 available, uint256 amount)",
+    "error InsufficientPromoReserve(uint256 available, uint256 needed)",
+    "error CreditNotAccepted(address collateral)",
+    "error LiquidityNotAccepted(address collateral)",
+    "error NoLiquidity(bytes8 event_id, address provider)",
+    "error NoBonus(bytes8 event_id, address sponsor)",
+    "error BonusNotRefundable(bytes8 event_id)",
+    // CoreEvents
+    "error AlreadyAdded()",
+    "error NotStartedYet()",
+    "error WrongWinner()",
+    "error NotAuthorized()",
+    "error OwnableUnauthorizedAccount(address account)",
+    "error UnauthorizedOracle(address account)",
+    "error OwnableInvalidOwner(address owner)",
+    // Vault
+    "error Zero(address account)",
+    "error AlreadySet(address account)",
+    "error TransferFailed(address to, uint256 amount)",
+    "error InvalidAsset(address asset)",
+    "error TooManyExcluded(uint256 max)",
+    "error InvalidDuration(uint64 duration)",
+];
+
+/// Decodes revert data into a readable error, e.g. `InvalidTeam(team: 3)`.
+///
+/// Falls back to `Error(string)` reasons and finally to the raw hex data.
+pub fn decode_revert_data(data: &[u8]) -> String {
+    if let Some(reason) = String::decode_with_selector(data) {
+        return reason;
+    }
+    if data.len() < 4 {
+        return format!("0x{}", hex::encode(data));
+    }
+
+    for signature in KNOWN_ERRORS {
+        let Ok(error) = HumanReadableParser::parse_error(signature) else {
+            continue;
+        };
+        if error.signature()[..4] != data[..4] {
+            continue;
+        }
+        if let Ok(tokens) = error.decode(&data[4..]) {
+            return format_error(&error, &tokens);
+        }
+    }
+
+    format!("0x{}", hex::encode(data))
+}
+
+fn format_error(error: &AbiError, tokens: &[Token]) -> String {
+    let params: Vec<String> = error
+        .inputs
+        .iter()
+        .zip(tokens)
+        .map(|(param, token)| format!("{}: {}", param.name, token))
+        .collect();
+
+    format!("{}({})", error.name, params.join(", "))
+}
@@ -5,6 +5,9 @@ use serde::de::value;
 use serde_json::from_str;
 use std::sync::Arc;
 
+pub mod errors;
+use errors::decode_revert_data;
+
 /// Generalized function to call a contract method (unsigned)
 pub async fn call_contract_method<T: Detokenize>(
     method_name: &str,
@@ -25,11 +28,20 @@ pub async fn call_contract_method<T: Detokenize>(
     let abi: Abi = from_str(abi_json).wrap_err("Error parsing ABI")?;
     let contract = Contract::new(contract_address, abi, Arc::new(provider));
 
-    let result: T = contract
-        .method::<_, T>(method_name, args)?
-        .call()
-        .await
-        .wrap_err(format!("Failed to call '{}' method", method_name))?;
+    let result: T = match contract.method::<_, T>(method_name, args)?.call().await {
+        Ok(result) => result,
+        Err(e) => {
+            return Err(match e.as_revert() {
+                Some(data) => eyre::eyre!(
+                    "Failed to call '{}' method: {}",
+                    method_name,
+                    decode_revert_data(data)
+                ),
+                None => eyre::Report::new(e)
+                    .wrap_err(format!("Failed to call '{}' method", method_name)),
+            });
+        }
+    };
 
     Ok(result)
 }
@@ -57,7 +69,8 @@ pub async fn call_contract_method_signed(
         Ok(gas) => gas,
         Err(e) => {
             let revert_reason = e
-                .decode_revert::<String>()
+                .as_revert()
+                .map(|data| decode_revert_data(data))
                 .unwrap_or_else(|| "Unknown error".to_string());
             return Err(eyre::eyre!(
                 "Transaction simulation failed: {}",
@@ -10,6 +10,7 @@
 - Oracle: Result verification system
 */
 
+mod benchmarks;
 mod core_events;
 mod stake_engine;
 mod call_contract;
@@ -87,8 +88,22 @@ async fn main() -> Result<()> {
     core_events::initialize(_owner_wallet).await?;
     stake_engine::initialize(&env.erc20aton_address, &env.core_address, _owner_wallet).await?;
     erc20aton::update_stake_engine(&env.stake_address, true,_owner_wallet).await?;
+
+    // 4.6 Keep protocol balances out of commission distribution
+    vault::set_excluded(&env.stake_address, true, _owner_wallet).await?;
+    vault::set_excluded(&env.vault_address, true, _owner_wallet).await?;
+    vault::set_excluded(&env.erc20aton_address, true, _owner_wallet).await?;
+    vault::effective_supply().await?;
     core_events::set_oracle(_oracle_wallet.address, _owner_wallet).await?;
 
+    // 4.7 Optional gas benchmarks, e.g. BENCH_OPEN_EVENTS=1,10,50
+    if let Ok(sizes) = std::env::var("BENCH_OPEN_EVENTS") {
+        println!("\n[Gas Benchmarks]");
+        for size in sizes.split(',').filter_map(|size| size.trim().parse::<u64>().ok()) {
+            benchmarks::close_event_gas(size, _oracle_wallet).await?;
+        }
+    }
+
     // 5. Parimutuel Event Lifecycle Test
     println!("\n[Phase 2: Event Simulation]");
     
@@ -104,10 +119,15 @@ async fn main() -> Result<()> {
     eth_balance(_player1_wallet.address).await?;
     
     // Place sample bets (Parimutuel pool creation)
+    stake_engine::quote(&event_id, 1, U256::from(10)).await?;
     stake_engine::stake(&event_id, U256::from(0), U256::from(10), 1, _player1_wallet).await?;
     // stake_engine::stake(&event_id, U256::from(0), U256::from(2), 2, _player2_wallet).await?;
     eth_balance(_player1_wallet.address).await?;
-    
+    assert_contract(
+        stake_engine::odds(&event_id).await?.len() == 2,
+        "Odds should be quoted for both teams"
+    ).await?;
+
     // Verify token emission from bets
     assert_contract(
         erc20aton::total_supply().await? > U256::zero(),
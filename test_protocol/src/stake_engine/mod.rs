@@ -175,39 +175,106 @@ pub async fn stake(
     Ok(())
 }
 
+    // function stakeWithLimits(string calldata _event_id, uint256 _amount, uint8 _team, uint256 _min_payout_multiplier, uint64 _deadline) external payable returns (bool);
+pub async fn stake_with_limits(
+    event_id: &str,
+    _amount: U256,
+    _value: U256,
+    _team: u8,
+    _min_payout_multiplier: U256,
+    _deadline: u64,
+    _wallet: &Wallet,
+) -> Result<()> {
+    let abi_json = r#"[
+        {
+            "inputs": [
+                { "internalType": "string", "name": "_event_id", "type": "string" },
+                { "internalType": "uint256", "name": "_amount", "type": "uint256" },
+                { "internalType": "uint8", "name": "_team", "type": "uint8" },
+                { "internalType": "uint256", "name": "_min_payout_multiplier", "type": "uint256" },
+                { "internalType": "uint64", "name": "_deadline", "type": "uint64" }
+            ],
+            "name": "stakeWithLimits",
+            "outputs": [
+                { "internalType": "bool", "name": "", "type": "bool" }
+            ],
+            "stateMutability": "payable",
+            "type": "function"
+        }
+    ]"#;
+
+    let env = get_env_vars();
+    let stake_address = env.stake_address;
+    let rpc_url = env.rpc_url;
+    let chain_id = env.chain_id;
 
+    let wallet = _wallet
+        .private_key
+        .parse::<LocalWallet>()?
+        .with_chain_id(chain_id);
+    let signer = Arc::new(SignerMiddleware::new(
+        Provider::<Http>::try_from(rpc_url)?,
+        wallet,
+    ));
 
-pub async fn get_stakes(event_id: &str, page_size: u64, page: u64) -> Result<Vec<(U256, u8, u64)>> {
+    let receipt = call_contract_method_signed(
+        "stakeWithLimits",
+        (event_id.to_string(), _amount, _team, _min_payout_multiplier, _deadline),
+        abi_json,
+        &stake_address,
+        signer,
+        _value,
+    )
+    .await?;
+
+    match receipt {
+        Some(receipt) => println!("\nStake successful. Gas used: {:?}", receipt.gas_used),
+        None => println!("\nTransaction executed but no receipt received"),
+    }
+
+    Ok(())
+}
+
+
+
+pub async fn get_stakes(
+    event_id: &str,
+    offset: u64,
+    limit: u64,
+) -> Result<(Vec<(Address, U256, u8, u64)>, u64, u64)> {
     let abi_json = r#"[
         {
             "type": "function",
             "name": "getStakes",
             "inputs": [
                 {"name": "_event_id", "type": "string", "internalType": "string"},
-                {"name": "page_size", "type": "uint64", "internalType": "uint64"},
-                {"name": "page", "type": "uint64", "internalType": "uint64"}
+                {"name": "offset", "type": "uint64", "internalType": "uint64"},
+                {"name": "limit", "type": "uint64", "internalType": "uint64"}
             ],
             "outputs": [
                 {
                     "type": "tuple[]",
                     "internalType": "struct MyContract.Stake[]",
                     "components": [
+                        {"type": "address", "name": "player", "internalType": "address"},
                         {"type": "uint256", "name": "amount", "internalType": "uint256"},
                         {"type": "uint8", "name": "team", "internalType": "uint8"},
                         {"type": "uint64", "name": "timestamp", "internalType": "uint64"}
                     ]
-                }
+                },
+                {"type": "uint64", "name": "total", "internalType": "uint64"},
+                {"type": "uint64", "name": "nextOffset", "internalType": "uint64"}
             ],
             "stateMutability": "view"
         }
     ]"#;
 
     let env = get_env_vars();
-    let result: Vec<(U256, u8, u64)> = call_contract_method(
+    let result: (Vec<(Address, U256, u8, u64)>, u64, u64) = call_contract_method(
         "getStakes",
-        (event_id.to_string(), page_size, page),
+        (event_id.to_string(), offset, limit),
         abi_json,
-        &env.erc20aton_address,
+        &env.stake_address,
         &env.rpc_url,
     )
     .await?;
@@ -254,4 +321,116 @@ pub async fn get_open_events_list(page_size: u64, page: u64) -> Result<Vec<(Stri
     .await?;
 
     Ok(result)
-}
\ No newline at end of file
+}
+
+    // function quote(string calldata _event_id, uint8 _team, uint256 _amount) external view returns (uint256, uint256, uint256);
+pub async fn quote(event_id: &str, team: u8, amount: U256) -> Result<(U256, U256, U256)> {
+    let abi_json = r#"[
+        {
+            "type": "function",
+            "name": "quote",
+            "inputs": [
+                {"name": "_event_id", "type": "string", "internalType": "string"},
+                {"name": "_team", "type": "uint8", "internalType": "uint8"},
+                {"name": "_amount", "type": "uint256", "internalType": "uint256"}
+            ],
+            "outputs": [
+                {"name": "payout", "type": "uint256", "internalType": "uint256"},
+                {"name": "odds", "type": "uint256", "internalType": "uint256"},
+                {"name": "commission", "type": "uint256", "internalType": "uint256"}
+            ],
+            "stateMutability": "view"
+        }
+    ]"#;
+
+    let env = get_env_vars();
+    let result: (U256, U256, U256) = call_contract_method(
+        "quote",
+        (event_id.to_string(), team, amount),
+        abi_json,
+        &env.stake_address,
+        &env.rpc_url,
+    )
+    .await?;
+
+    println!(
+        "\nQuote {} team {} amount {}: payout {}, odds {}, commission {}",
+        event_id, team, amount, result.0, result.1, result.2
+    );
+
+    Ok(result)
+}
+
+    // function stakeLimits(string calldata _event_id) external view returns (uint256, uint256, uint256, uint256);
+pub async fn stake_limits(event_id: &str) -> Result<(U256, U256, U256, U256)> {
+    let abi_json = r#"[
+        {
+            "type": "function",
+            "name": "stakeLimits",
+            "inputs": [
+                {"name": "_event_id", "type": "string", "internalType": "string"}
+            ],
+            "outputs": [
+                {"name": "min_stake", "type": "uint256", "internalType": "uint256"},
+                {"name": "max_player_stake", "type": "uint256", "internalType": "uint256"},
+                {"name": "max_outcome_pool", "type": "uint256", "internalType": "uint256"},
+                {"name": "max_imbalance", "type": "uint256", "internalType": "uint256"}
+            ],
+            "stateMutability": "view"
+        }
+    ]"#;
+
+    let env = get_env_vars();
+    let result: (U256, U256, U256, U256) = call_contract_method(
+        "stakeLimits",
+        (event_id.to_string(),),
+        abi_json,
+        &env.stake_address,
+        &env.rpc_url,
+    )
+    .await?;
+
+    println!(
+        "\nStake limits {}: min {}, max per player {}, max per outcome {}, max imbalance {}",
+        event_id, result.0, result.1, result.2, result.3
+    );
+
+    Ok(result)
+}
+
+    // function odds(string calldata _event_id) external view returns ((uint8, uint256, uint256)[] memory);
+pub async fn odds(event_id: &str) -> Result<Vec<(u8, U256, U256)>> {
+    let abi_json = r#"[
+        {
+            "type": "function",
+            "name": "odds",
+            "inputs": [
+                {"name": "_event_id", "type": "string", "internalType": "string"}
+            ],
+            "outputs": [
+                {
+                    "type": "tuple[]",
+                    "internalType": "struct StakeEngine.Odds[]",
+                    "components": [
+                        {"type": "uint8", "name": "team", "internalType": "uint8"},
+                        {"type": "uint256", "name": "pool", "internalType": "uint256"},
+                        {"type": "uint256", "name": "odds", "internalType": "uint256"}
+                    ]
+                }
+            ],
+            "stateMutability": "view"
+        }
+    ]"#;
+
+    let env = get_env_vars();
+    let result: Vec<(u8, U256, U256)> = call_contract_method(
+        "odds",
+        (event_id.to_string(),),
+        abi_json,
+        &env.stake_address,
+        &env.rpc_url,
+    )
+    .await?;
+
+    Ok(result)
+}
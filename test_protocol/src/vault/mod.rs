@@ -9,7 +9,9 @@ use crate::constants::wallets::{Wallet, WALLETS};
 
 
 /// Function to get the summary of a specific address
-pub async fn summary(player_address: &str) -> Result<(U256, U256, U256, U256, U256)> {
+pub async fn summary(
+    player_address: &str,
+) -> Result<(U256, U256, U256, U256, U256, Vec<(Address, U256, U256, U256)>)> {
     let abi_json = r#"[
         {
             "inputs": [
@@ -21,7 +23,18 @@ pub async fn summary(player_address: &str) -> Result<(U256, U256, U256, U256, U2
                 { "internalType": "uint256", "name": "", "type": "uint256" },
                 { "internalType": "uint256", "name": "", "type": "uint256" },
                 { "internalType": "uint256", "name": "", "type": "uint256" },
-                { "internalType": "uint256", "name": "", "type": "uint256" }
+                { "internalType": "uint256", "name": "", "type": "uint256" },
+                {
+                    "type": "tuple[]",
+                    "internalType": "struct Vault.AssetSummary[]",
+                    "name": "",
+                    "components": [
+                        { "internalType": "address", "name": "asset", "type": "address" },
+                        { "internalType": "uint256", "name": "unclaimed", "type": "uint256" },
+                        { "internalType": "uint256", "name": "claimed", "type": "uint256" },
+                        { "internalType": "uint256", "name": "totalDeposited", "type": "uint256" }
+                    ]
+                }
             ],
             "stateMutability": "view",
             "type": "function"
@@ -35,7 +48,14 @@ pub async fn summary(player_address: &str) -> Result<(U256, U256, U256, U256, U2
     let player: Address = player_address.parse()?;
 
     // Call the contract method and get the tuple of values
-    let (val1, val2, val3, val4, val5): (U256, U256, U256, U256, U256) = call_contract_method(
+    let (val1, val2, val3, val4, val5, assets): (
+        U256,
+        U256,
+        U256,
+        U256,
+        U256,
+        Vec<(Address, U256, U256, U256)>,
+    ) = call_contract_method(
         "summary",
         player, // Pass player as argument
         abi_json,
@@ -48,8 +68,14 @@ pub async fn summary(player_address: &str) -> Result<(U256, U256, U256, U256, U2
         "\nSummary of {}: {}, {}, {}, {}, {}",
         player, val1, val2, val3, val4, val5
     );
+    for (asset, unclaimed, claimed, total) in &assets {
+        println!(
+            "  Reward asset {}: unclaimed {}, claimed {}, total deposited {}",
+            asset, unclaimed, claimed, total
+        );
+    }
 
-    Ok((val1, val2, val3, val4, val5))
+    Ok((val1, val2, val3, val4, val5, assets))
 }
 
 
@@ -150,6 +176,178 @@ pub async fn accumulate_aton(amount: U256,_wallet: &Wallet) -> Result<()> {
 }
 
 
+pub async fn set_excluded(account: &str, excluded: bool, _wallet: &Wallet) -> Result<()> {
+    let abi_json = r#"[
+        {
+            "inputs": [
+                { "internalType": "address", "name": "account", "type": "address" },
+                { "internalType": "bool", "name": "excluded", "type": "bool" }
+            ],
+            "name": "setExcluded",
+            "outputs": [{ "internalType": "bool", "name": "", "type": "bool" }],
+            "stateMutability": "nonpayable",
+            "type": "function"
+        }
+    ]"#;
+
+    let env = get_env_vars();
+
+    let vault_address = env.vault_address;
+    let rpc_url = env.rpc_url;
+    let chain_id = env.chain_id;
+
+    // Create signer from private key
+    let wallet = _wallet
+        .private_key
+        .parse::<LocalWallet>()?
+        .with_chain_id(chain_id);
+    let signer = Arc::new(SignerMiddleware::new(
+        Provider::<Http>::try_from(rpc_url)?,
+        wallet,
+    ));
+
+    let account: Address = account.parse()?;
+
+    let receipt = call_contract_method_signed(
+        "setExcluded",
+        (account, excluded),
+        abi_json,
+        &vault_address,
+        signer,
+        U256::zero(), // No value sent
+    )
+    .await?;
+
+    match receipt {
+        Some(receipt) => println!("\nTransaction successful: {:?}", receipt.gas_used),
+        None => println!("\nTransaction executed successfully, but no receipt was returned."),
+    }
+
+    Ok(())
+}
+
+pub async fn claim(to: &str, _wallet: &Wallet) -> Result<()> {
+    let abi_json = r#"[
+        {
+            "inputs": [{ "internalType": "address", "name": "to", "type": "address" }],
+            "name": "claim",
+            "outputs": [{ "internalType": "uint256", "name": "", "type": "uint256" }],
+            "stateMutability": "nonpayable",
+            "type": "function"
+        }
+    ]"#;
+
+    let env = get_env_vars();
+
+    let vault_address = env.vault_address;
+    let rpc_url = env.rpc_url;
+    let chain_id = env.chain_id;
+
+    // Create signer from private key
+    let wallet = _wallet
+        .private_key
+        .parse::<LocalWallet>()?
+        .with_chain_id(chain_id);
+    let signer = Arc::new(SignerMiddleware::new(
+        Provider::<Http>::try_from(rpc_url)?,
+        wallet,
+    ));
+
+    let to: Address = to.parse()?;
+
+    let receipt = call_contract_method_signed(
+        "claim",
+        to,
+        abi_json,
+        &vault_address,
+        signer,
+        U256::zero(), // No value sent
+    )
+    .await?;
+
+    match receipt {
+        Some(receipt) => println!("\nClaim successful: {:?}", receipt.gas_used),
+        None => println!("\nTransaction executed successfully, but no receipt was returned."),
+    }
+
+    Ok(())
+}
+
+/// Function to get a page of the claim history of a specific address
+pub async fn get_claim_history(
+    player_address: &str,
+    page_size: u64,
+    page: u64,
+) -> Result<Vec<(U256, Address, u64)>> {
+    let abi_json = r#"[
+        {
+            "inputs": [
+                { "internalType": "address", "name": "player", "type": "address" },
+                { "internalType": "uint64", "name": "page_size", "type": "uint64" },
+                { "internalType": "uint64", "name": "page", "type": "uint64" }
+            ],
+            "name": "getClaimHistory",
+            "outputs": [
+                {
+                    "type": "tuple[]",
+                    "internalType": "struct Vault.Claim[]",
+                    "components": [
+                        { "internalType": "uint256", "name": "amount", "type": "uint256" },
+                        { "internalType": "address", "name": "to", "type": "address" },
+                        { "internalType": "uint64", "name": "timestamp", "type": "uint64" }
+                    ]
+                }
+            ],
+            "stateMutability": "view",
+            "type": "function"
+        }
+    ]"#;
+
+    let env = get_env_vars();
+    let player: Address = player_address.parse()?;
+
+    let claims: Vec<(U256, Address, u64)> = call_contract_method(
+        "getClaimHistory",
+        (player, page_size, page),
+        abi_json,
+        &env.vault_address,
+        &env.rpc_url,
+    )
+    .await?;
+
+    println!("\nClaim history of {}: {:?}", player, claims);
+
+    Ok(claims)
+}
+
+/// Returns the ATON supply that takes part in commission distribution
+pub async fn effective_supply() -> Result<U256> {
+    let abi_json = r#"[
+        {
+            "inputs": [],
+            "name": "effectiveSupply",
+            "outputs": [{ "internalType": "uint256", "name": "", "type": "uint256" }],
+            "stateMutability": "view",
+            "type": "function"
+        }
+    ]"#;
+
+    let env = get_env_vars();
+
+    let supply: U256 = call_contract_method(
+        "effectiveSupply",
+        (),
+        abi_json,
+        &env.vault_address,
+        &env.rpc_url,
+    )
+    .await?;
+
+    println!("\nEffective supply: {}", supply);
+
+    Ok(supply)
+}
+
 // /**
 //  * This file was automatically generated by Stylus and represents a Rust program.
 //  * For more information, please see [The Stylus SDK](https://github.com/OffchainLabs/stylus-sdk-rs).
@@ -167,13 +365,67 @@ pub async fn accumulate_aton(amount: U256,_wallet: &Wallet) -> Result<()> {
 
 //     function accumulateAton2(uint256 amount) external returns (bool);
 
-//     function summary(address player) external view returns (uint256, uint256, uint256, uint256, uint256);
+//     function summary(address player) external view returns (uint256, uint256, uint256, uint256, uint256, (address, uint256, uint256, uint256)[] memory);
 
 //     function playerCommission(address player) external view returns (uint256);
 
-//     function clearCommission(address player) external;
+//     function clearCommission(address player, uint256 paid) external;
+
+//     function owner() external view returns (address);
+
+//     function setExcluded(address account, bool excluded) external returns (bool);
+
+//     function isExcluded(address account) external view returns (bool);
+
+//     function excludedBalances() external view returns ((address, uint256)[] memory);
+
+//     function excludedSupply() external view returns (uint256);
+
+//     function effectiveSupply() external view returns (uint256);
+
+//     function undistributedCommission() external view returns (uint256);
+
+//     function setRewardDuration(uint64 duration) external returns (bool);
+
+//     function rewardDuration() external view returns (uint64);
+
+//     function rewardRate() external view returns (uint256);
+
+//     function periodFinish() external view returns (uint64);
+
+//     function rewardStream(address asset) external view returns (uint256, uint64);
+
+//     function commissionPerToken() external view returns (uint256);
+
+//     function earned(address player) external view returns (uint256);
+
+//     function claim(address to) external returns (uint256);
+
+//     function claimCount(address player) external view returns (uint64);
+
+//     function setRewardAsset(address asset, bool whitelisted) external returns (bool);
+
+//     function rewardAssets() external view returns (address[] memory);
+
+//     function isRewardAsset(address asset) external view returns (bool);
+
+//     function accumulateEth() external payable returns (bool);
+
+//     function accumulateReward(address asset, uint256 amount) external returns (bool);
+
+//     function pendingReward(address asset, address player) external view returns (uint256);
+
+//     function claimReward(address asset, address to) external returns (uint256);
+
+//     function getClaimHistory(address player, uint64 page_size, uint64 page) external view returns ((uint256, address, uint64)[] memory);
 
 //     error Zero(address);
 
 //     error UnauthorizedAccount(address);
+
+//     error AlreadySet(address);
+
+//     error TransferFailed(address, uint256);
+
+//     error InvalidAsset(address);
 // }
\ No newline at end of file